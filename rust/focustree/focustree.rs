@@ -2,8 +2,6 @@
 //! from the leaves of the tree, while supporting the option for the tree
 //! to skip or focus certain branches.
 
-use specimen__writable::Writable;
-
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Flag {
     #[default]
@@ -12,10 +10,51 @@ pub enum Flag {
     Focus,
 }
 
-#[derive(Debug, Default)]
-pub struct FlagStat {
+/// An unexpected situation met during the traversal of the tree.
+#[derive(Debug)]
+pub struct TreeWarning<TValue> {
+    /// the value of the node the warning is about.
+    pub value: TValue,
+    pub message: &'static str,
+}
+
+/// The statistics of a traversal. They hold the values of the focused and
+/// skipped nodes, rather than their count only, so that the user of the tree
+/// can report where the flags are.
+#[derive(Debug)]
+pub struct FlagStat<TValue> {
+    /// the number of nodes whose FOCUS flag is honored.
     pub focus_count: usize,
+    /// the number of skipped nodes among the selected nodes.
     pub skip_count: usize,
+    /// the values of all the focused nodes, including those whose FOCUS flag
+    /// is ignored, in prefix order.
+    pub focus_value_vec: Vec<TValue>,
+    /// the values of all the skipped nodes, including those outside of the
+    /// focused nodes, in prefix order.
+    pub skip_value_vec: Vec<TValue>,
+    /// the number of focused values, among the values held by the nodes
+    /// which are not skipped.
+    pub focused_value_count: usize,
+    /// the number of skipped values, among the values held by the nodes
+    /// which are not skipped.
+    pub skipped_value_count: usize,
+    /// the warnings issued during the traversal.
+    pub warning_vec: Vec<TreeWarning<TValue>>,
+}
+
+impl<TValue> Default for FlagStat<TValue> {
+    fn default() -> Self {
+        FlagStat {
+            focus_count: 0,
+            skip_count: 0,
+            focus_value_vec: Vec::new(),
+            skip_value_vec: Vec::new(),
+            focused_value_count: 0,
+            skipped_value_count: 0,
+            warning_vec: Vec::new(),
+        }
+    }
 }
 
 pub trait Tree<TValue> {
@@ -27,9 +66,12 @@ pub trait Tree<TValue> {
     fn get_children(&self) -> Vec<&dyn Tree<TValue>>;
     /// returns the value of the current node.
     fn get_value(&self) -> TValue;
-    /// a function that is called when some unexpected situation occurs
-    /// during the traversal of the current node.
-    fn warning(&self, message: &str, stdout: &mut Writable);
+    /// returns the flags set on the individual values held by the current
+    /// node, if any. A node which holds focused values is selected like a
+    /// focused node.
+    fn get_value_flag_vec(&self) -> Vec<Flag> {
+        Vec::new()
+    }
 }
 
 // This function calls find_focused_nodes. If no node is focused, it
@@ -38,12 +80,15 @@ pub trait Tree<TValue> {
 pub fn extract_focused_leaf_values<TValue>(
     tree: &dyn Tree<TValue>,
     destination: &mut Vec<TValue>,
-    flag_stat: &mut FlagStat,
-    stdout: &mut Writable,
+    flag_stat: &mut FlagStat<TValue>,
 ) {
     let mut focused_node_vec = Vec::new();
-    extract_focused_nodes(tree, &mut focused_node_vec, stdout);
-    flag_stat.focus_count = focused_node_vec.len();
+    extract_focused_nodes(tree, &mut focused_node_vec, flag_stat);
+    // The nodes selected for their focused values are counted with the values
+    flag_stat.focus_count = focused_node_vec
+        .iter()
+        .filter(|node| node.get_flag() == Flag::Focus)
+        .count();
     if focused_node_vec.is_empty() {
         focused_node_vec.push(tree);
     }
@@ -52,41 +97,134 @@ pub fn extract_focused_leaf_values<TValue>(
     }
 }
 
-/// This function is used to find the nodes that are focused. If a node
-/// has focused descendants and is itself focused, then it is considered
-/// not focused and a warning is issued.
+/// This function is used to find the nodes that are focused, either by
+/// their flag or by their values, to collect the focused and skipped nodes
+/// of the whole tree, and to count the flags of the values. If a node has
+/// focused descendants and is itself focused, then it is considered not
+/// focused and a warning is issued.
 fn extract_focused_nodes<'vec, 'node: 'vec, TValue>(
     tree: &'node dyn Tree<TValue>,
     focused_node_vec: &'vec mut Vec<&'node dyn Tree<TValue>>,
-    stdout: &mut Writable,
+    flag_stat: &mut FlagStat<TValue>,
 ) {
-    if tree.get_flag() == Flag::Skip {
-        return;
+    match tree.get_flag() {
+        Flag::Skip => {
+            flag_stat.skip_value_vec.push(tree.get_value());
+            return;
+        }
+        Flag::Focus => flag_stat.focus_value_vec.push(tree.get_value()),
+        Flag::None => {}
+    }
+    let value_flag_vec = tree.get_value_flag_vec();
+    for value_flag in value_flag_vec.iter() {
+        match value_flag {
+            Flag::Focus => flag_stat.focused_value_count += 1,
+            Flag::Skip => flag_stat.skipped_value_count += 1,
+            _ => {}
+        }
     }
     let initial_length = focused_node_vec.len();
     for child in tree.get_children() {
-        extract_focused_nodes(child, focused_node_vec, stdout);
+        extract_focused_nodes(child, focused_node_vec, flag_stat);
     }
-    if tree.get_flag() == Flag::Focus {
+    if tree.get_flag() == Flag::Focus || value_flag_vec.contains(&Flag::Focus) {
         if focused_node_vec.len() <= initial_length {
             focused_node_vec.push(tree);
         } else {
-            tree.warning(
-                "A node with focused descendants is itself focused. \
+            flag_stat.warning_vec.push(TreeWarning {
+                value: tree.get_value(),
+                message: "A node with focused descendants is itself focused. \
                 It has been considered not focused in favor of its \
                 focused descendants",
-                stdout,
-            );
+            });
         }
     }
 }
 
+/// Why a node is selected or not by `extract_focused_leaf_values`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The node is focused, inside a focused node, or no node is focused.
+    Selected,
+    /// The node or one of its ancestors is skipped.
+    Skipped,
+    /// Some nodes are focused, and the node neither is nor contains one.
+    OutsideFocus,
+    /// The node is not focused itself but contains focused nodes.
+    ContainsFocus,
+    /// The node is focused, but its focus is ignored in favor of its focused
+    /// descendants.
+    FocusIgnored,
+}
+
+/// returns the values of all the nodes of the tree, in prefix order, along
+/// with the reason why they are selected or not. It explains the decisions
+/// of `extract_focused_leaf_values`.
+pub fn explain_selection<TValue>(tree: &dyn Tree<TValue>) -> Vec<(TValue, Selection)> {
+    let mut focused_node_vec = Vec::new();
+    extract_focused_nodes(tree, &mut focused_node_vec, &mut FlagStat::default());
+    let mut explanation_vec = Vec::new();
+    let inherited = if focused_node_vec.is_empty() {
+        Some(Selection::Selected)
+    } else {
+        None
+    };
+    explain_node(tree, inherited, &focused_node_vec, &mut explanation_vec);
+    explanation_vec
+}
+
+/// The inherited selection is set once an ancestor of the node is selected
+/// or skipped as a whole.
+fn explain_node<TValue>(
+    tree: &dyn Tree<TValue>,
+    inherited: Option<Selection>,
+    focused_node_vec: &[&dyn Tree<TValue>],
+    explanation_vec: &mut Vec<(TValue, Selection)>,
+) {
+    let selection = if tree.get_flag() == Flag::Skip {
+        Selection::Skipped
+    } else if let Some(selection) = inherited {
+        selection
+    } else if focused_node_vec
+        .iter()
+        .any(|focused_node| std::ptr::addr_eq(*focused_node, tree))
+    {
+        Selection::Selected
+    } else if tree.get_flag() == Flag::Focus || tree.get_value_flag_vec().contains(&Flag::Focus) {
+        Selection::FocusIgnored
+    } else if contains_focused_node(tree, focused_node_vec) {
+        Selection::ContainsFocus
+    } else {
+        Selection::OutsideFocus
+    };
+    explanation_vec.push((tree.get_value(), selection));
+    let inherited = match selection {
+        Selection::Selected | Selection::Skipped | Selection::OutsideFocus => Some(selection),
+        Selection::ContainsFocus | Selection::FocusIgnored => None,
+    };
+    for child in tree.get_children() {
+        explain_node(child, inherited, focused_node_vec, explanation_vec);
+    }
+}
+
+fn contains_focused_node<TValue>(
+    tree: &dyn Tree<TValue>,
+    focused_node_vec: &[&dyn Tree<TValue>],
+) -> bool {
+    tree.get_children().into_iter().any(|child| {
+        focused_node_vec
+            .iter()
+            .any(|focused_node| std::ptr::addr_eq(*focused_node, child))
+            || contains_focused_node(child, focused_node_vec)
+    })
+}
+
 /// This function produces the synthesis of the values of the leaves of
 /// the node it receives.
 fn get_leaf_values<TValue>(
     tree: &dyn Tree<TValue>,
     value_vec: &mut Vec<TValue>,
-    flag_stat: &mut FlagStat,
+    flag_stat: &mut FlagStat<TValue>,
 ) {
     if tree.get_flag() == Flag::Skip {
         flag_stat.skip_count += 1;
@@ -128,7 +266,6 @@ mod tests {
             fn get_value(&self) -> i32 {
                 self.value
             }
-            fn warning(&self, _message: &str, _stdout: &mut Writable) {}
         }
 
         // This tree is used to test the library.
@@ -202,9 +339,137 @@ mod tests {
 
         let mut actual_result = Vec::new();
         let mut flag_stat = FlagStat::default();
-        let mut stdout = Writable::Vec(Vec::new());
-        extract_focused_leaf_values(&tree, &mut actual_result, &mut flag_stat, &mut stdout);
+        extract_focused_leaf_values(&tree, &mut actual_result, &mut flag_stat);
 
         assert_eq!(actual_result, vec![9, 10, 6]);
+        // Node 2 is focused and has a focused descendant
+        let warned_value_vec: Vec<i32> = flag_stat
+            .warning_vec
+            .iter()
+            .map(|warning| warning.value)
+            .collect();
+        assert_eq!(warned_value_vec, vec![2]);
+        // The ignored FOCUS flag of node 2 is listed, but not counted
+        assert_eq!(flag_stat.focus_value_vec, vec![2, 7, 6]);
+        assert_eq!(flag_stat.focus_count, 2);
+
+        use Selection::*;
+        assert_eq!(
+            explain_selection(&tree),
+            vec![
+                (1, ContainsFocus),
+                (2, FocusIgnored),
+                (4, OutsideFocus),
+                (5, ContainsFocus),
+                (7, Selected),
+                (9, Selected),
+                (10, Selected),
+                (8, OutsideFocus),
+                (3, ContainsFocus),
+                (6, Selected),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_collects_the_skipped_nodes_of_the_whole_tree() {
+        struct Node {
+            value: i32,
+            flag: Flag,
+            children: Vec<Node>,
+        }
+
+        impl Tree<i32> for Node {
+            fn is_leaf(&self) -> bool {
+                self.children.is_empty()
+            }
+            fn get_flag(&self) -> Flag {
+                self.flag
+            }
+            fn get_children(&self) -> Vec<&dyn Tree<i32>> {
+                self.children.iter().map(|x| x as &dyn Tree<i32>).collect()
+            }
+            fn get_value(&self) -> i32 {
+                self.value
+            }
+        }
+
+        let leaf = |value: i32, flag: Flag| Node {
+            value,
+            flag,
+            children: vec![],
+        };
+        // The node 4 is skipped inside the focused node 2, the node 5 outside
+        let tree = Node {
+            value: 1,
+            flag: Flag::None,
+            children: vec![
+                Node {
+                    value: 2,
+                    flag: Flag::Focus,
+                    children: vec![leaf(3, Flag::None), leaf(4, Flag::Skip)],
+                },
+                leaf(5, Flag::Skip),
+                leaf(6, Flag::None),
+            ],
+        };
+
+        let mut actual_result = Vec::new();
+        let mut flag_stat = FlagStat::default();
+        extract_focused_leaf_values(&tree, &mut actual_result, &mut flag_stat);
+
+        assert_eq!(actual_result, vec![3]);
+        assert_eq!(flag_stat.skip_value_vec, vec![4, 5]);
+        assert_eq!(flag_stat.skip_count, 1);
+    }
+
+    #[test]
+    fn it_selects_the_nodes_with_focused_values() {
+        struct Node {
+            value: i32,
+            value_flag_vec: Vec<Flag>,
+            children: Vec<Node>,
+        }
+
+        impl Tree<i32> for Node {
+            fn is_leaf(&self) -> bool {
+                self.children.is_empty()
+            }
+            fn get_flag(&self) -> Flag {
+                Flag::None
+            }
+            fn get_children(&self) -> Vec<&dyn Tree<i32>> {
+                self.children.iter().map(|x| x as &dyn Tree<i32>).collect()
+            }
+            fn get_value(&self) -> i32 {
+                self.value
+            }
+            fn get_value_flag_vec(&self) -> Vec<Flag> {
+                self.value_flag_vec.clone()
+            }
+        }
+
+        let leaf = |value: i32, value_flag_vec: Vec<Flag>| Node {
+            value,
+            value_flag_vec,
+            children: vec![],
+        };
+        let tree = Node {
+            value: 1,
+            value_flag_vec: vec![],
+            children: vec![
+                leaf(2, vec![Flag::Skip]),
+                leaf(3, vec![Flag::Focus, Flag::Skip]),
+            ],
+        };
+
+        let mut actual_result = Vec::new();
+        let mut flag_stat = FlagStat::default();
+        extract_focused_leaf_values(&tree, &mut actual_result, &mut flag_stat);
+
+        assert_eq!(actual_result, vec![3]);
+        assert_eq!(flag_stat.focus_count, 0);
+        assert_eq!(flag_stat.focused_value_count, 1);
+        assert_eq!(flag_stat.skipped_value_count, 2);
     }
 }
@@ -4,34 +4,100 @@ use std::rc::Rc;
 
 pub type Dict = HashMap<Box<str>, Box<str>>;
 
+/// A mark set on a single value of a key. When a key has focused values, the
+/// product iterator only produces these values; it never produces the
+/// skipped values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mark {
+    #[default]
+    None,
+    Focus,
+    Skip,
+}
+
+/// The values of each key, along with the marks of the values of the keys
+/// which have marked values.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub struct MultiStringMap(pub LinkedHashMap<Box<str>, Rc<[Box<str>]>>);
+pub struct MultiStringMap(
+    pub LinkedHashMap<Box<str>, Rc<[Box<str>]>>,
+    pub HashMap<Box<str>, Rc<[Mark]>>,
+);
 
 impl MultiStringMap {
     pub fn new() -> Self {
-        MultiStringMap(LinkedHashMap::new())
+        MultiStringMap(LinkedHashMap::new(), HashMap::new())
+    }
+    /// sets the values of the key, along with their marks, replacing the
+    /// previous values and marks of the key.
+    pub fn insert_marked(
+        &mut self,
+        key: Box<str>,
+        value_array: Rc<[Box<str>]>,
+        mark_array: &[Mark],
+    ) {
+        if mark_array.iter().any(|mark| *mark != Mark::None) {
+            self.1.insert(key.clone(), Rc::from(mark_array));
+        } else {
+            self.1.remove(&key);
+        }
+        self.0.insert(key, value_array);
+    }
+    /// returns the values of the key which the product iterator produces.
+    pub fn selected_values(&self, key: &str) -> Vec<Box<str>> {
+        let value_array = &self.0[key];
+        let mark_array = match self.1.get(key) {
+            Some(mark_array) => mark_array,
+            None => return value_array.to_vec(),
+        };
+        let wanted = if mark_array.contains(&Mark::Focus) {
+            |mark: &Mark| *mark == Mark::Focus
+        } else {
+            |mark: &Mark| *mark != Mark::Skip
+        };
+        value_array
+            .iter()
+            .zip(mark_array.iter())
+            .filter(|(_, mark)| wanted(mark))
+            .map(|(value, _)| value.clone())
+            .collect()
+    }
+    /// returns the number of combinations produced by the product iterator.
+    pub fn product_len(&self) -> usize {
+        self.0
+            .keys()
+            .map(|key| self.selected_values(key).len())
+            .product()
     }
     pub fn into_product_iterator(self) -> MultiStringMapProductIterator {
-        let reversed_key_array = self.0.keys().rev().cloned().collect::<Box<[Box<str>]>>();
+        // Only the selected values of the marked keys are iterated over
+        let mut map = MultiStringMap::new();
+        for key in self.0.keys() {
+            map.0
+                .insert(key.clone(), Rc::from(self.selected_values(key)));
+        }
+        let stopped = map.0.values().any(|value_array| value_array.is_empty());
+        let reversed_key_array = map.0.keys().rev().cloned().collect::<Box<[Box<str>]>>();
         let reversed_size_array = reversed_key_array
             .iter()
-            .map(|key| self.0[key].len())
+            .map(|key| map.0[key].len())
             .collect::<Box<[usize]>>();
         let reversed_index_array = vec![0; reversed_size_array.len()].into_boxed_slice();
 
         // Initialize the combination
         let mut combination = Dict::new();
-        for key in reversed_key_array.iter() {
-            combination.insert((*key).clone(), self.0[key][0].clone());
+        if !stopped {
+            for key in reversed_key_array.iter() {
+                combination.insert((*key).clone(), map.0[key][0].clone());
+            }
         }
 
         MultiStringMapProductIterator {
-            map: self,
+            map,
             reversed_key_array,
             reversed_size_array,
             reversed_index_array,
             first: true,
-            stopped: false,
+            stopped,
             combination,
         }
     }
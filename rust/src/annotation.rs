@@ -0,0 +1,154 @@
+//! The annotations reporter prints the failures and the diagnostics as GitHub
+//! Actions workflow commands, so that they show up inline on the YAML files
+//! of a pull request:
+//!
+//! ```text
+//! ::error file=spec/flag.yaml,line=12,col=7::FAIL[3]: Expected 2, got 3
+//! ```
+
+use crate::diagnostic::Diagnostic;
+use crate::diagnostic::Severity;
+use crate::report::Report;
+use crate::timing;
+use crate::FailStatus;
+use specimen__writable::Writable;
+use std::io;
+use std::io::Write;
+
+/// Splits a `path:line:column` location, as computed by
+/// `Nodule::get_location`, into a path, a line and a column. The column of
+/// the location is 0-based, and is converted to the 1-based column of the
+/// diagnostics. The path itself may contain colons.
+fn split_location(location: &str) -> Option<(&str, usize, usize)> {
+    let mut part_iter = location.rsplitn(3, ':');
+    let column: usize = part_iter.next()?.parse().ok()?;
+    let line = part_iter.next()?.parse().ok()?;
+    let path = part_iter.next()?;
+    Some((path, line, column + 1))
+}
+
+/// Escapes the data of a workflow command, which must fit on a single line.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes the value of a workflow command property.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn write_command(
+    stdout: &mut Writable,
+    command: &str,
+    position: Option<(&str, usize, usize)>,
+    message: &str,
+) -> io::Result<()> {
+    let Some((path, line, column)) = position else {
+        return writeln!(stdout, "::{command}::{}", escape_data(message));
+    };
+    writeln!(
+        stdout,
+        "::{command} file={},line={line},col={column}::{}",
+        escape_property(path),
+        escape_data(message)
+    )
+}
+
+pub fn write_diagnostics(diagnostic_slice: &[Diagnostic], stdout: &mut Writable) -> io::Result<()> {
+    for diagnostic in diagnostic_slice.iter() {
+        let command = match diagnostic.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        // The diagnostics about the options have no position
+        if diagnostic.line == 0 {
+            let message = format!("{}: {}", diagnostic.file_path, diagnostic.message);
+            write_command(stdout, command, None, &message)?;
+        } else {
+            let position = (&*diagnostic.file_path, diagnostic.line, diagnostic.column);
+            write_command(stdout, command, Some(position), &diagnostic.message)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_failures(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for tile in report.tile_vec.iter() {
+        if let Some(word) = tile.failure_word() {
+            let message = format!(
+                "{word}[{}]{}: {}",
+                tile.index,
+                tile.label_suffix(),
+                tile.message
+            );
+            // An unexpected pass only fails the run in strict mode
+            let command = match tile.status {
+                FailStatus::UnexpectedPass if !report.strict => "warning",
+                _ => "error",
+            };
+            write_command(
+                stdout,
+                command,
+                split_location(&tile.slab_location),
+                &message,
+            )?;
+        }
+    }
+    for skip in report.skip_vec.iter().filter(|skip| skip.expired) {
+        let message = format!("EXPIRED: The PENDING flag has expired: {}", skip.describe());
+        write_command(stdout, "error", split_location(&skip.location), &message)?;
+    }
+    Ok(())
+}
+
+pub fn write_pending(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for skip in report.skip_vec.iter() {
+        if !skip.expired && skip.reason.is_some() {
+            let message = format!("PENDING: {}", skip.describe());
+            write_command(stdout, "notice", split_location(&skip.location), &message)?;
+        }
+    }
+    for skip in report.condition_skip_vec.iter() {
+        let message = format!("SKIPPED: {} ({} tile(s))", skip.reason, skip.tile_count);
+        write_command(stdout, "notice", split_location(&skip.location), &message)?;
+    }
+    Ok(())
+}
+
+pub fn write_slow(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for tile in report.tile_vec.iter().filter(|tile| tile.is_slow()) {
+        let message = format!(
+            "SLOW[{}]{}: {}",
+            tile.index,
+            tile.label_suffix(),
+            timing::slow_message(tile.duration, tile.slow_threshold.unwrap_or_default())
+        );
+        write_command(
+            stdout,
+            "warning",
+            split_location(&tile.slab_location),
+            &message,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_locations_from_the_right() {
+        assert_eq!(split_location("a.yaml:3:5"), Some(("a.yaml", 3, 6)));
+        assert_eq!(split_location("C:/a.yaml:3:5"), Some(("C:/a.yaml", 3, 6)));
+        assert_eq!(split_location("a.yaml"), None);
+    }
+
+    #[test]
+    fn it_escapes_multiline_messages() {
+        assert_eq!(escape_data("50%\nof it"), "50%25%0Aof it");
+        assert_eq!(escape_property("a,b:c"), "a%2Cb%3Ac");
+    }
+}
@@ -0,0 +1,183 @@
+//! A baseline is the JSON record of the outcome of each tile of a run. A
+//! later run can be compared to it, to list the tiles which started or
+//! stopped failing, and the tiles which appeared or disappeared. The tiles
+//! are matched by their ID rather than by their location, so that inserting
+//! lines in a spec file does not change the comparison.
+
+use crate::json;
+use crate::report::Report;
+use crate::FailStatus;
+#[cfg(feature = "json")]
+use serde::Deserialize;
+#[cfg(feature = "json")]
+use serde::Serialize;
+use specimen__writable::Writable;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct BaselineTile {
+    pub id: String,
+    /// The file path followed by the name of the tile
+    pub name: String,
+    pub location: String,
+    /// `pass`, `fail`, `abort`, `panic`, `xfail` or `xpass`
+    pub status: String,
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Baseline {
+    pub tiles: Vec<BaselineTile>,
+}
+
+fn status_word(status: FailStatus) -> &'static str {
+    match status {
+        FailStatus::Pristine => "pass",
+        FailStatus::Failed => "fail",
+        FailStatus::Aborted => "abort",
+        FailStatus::Panicked => "panic",
+        FailStatus::ExpectedFailure => "xfail",
+        FailStatus::UnexpectedPass => "xpass",
+    }
+}
+
+/// returns false for the statuses of the tiles which failed without being
+/// expected to
+fn is_passing(status: &str) -> bool {
+    !matches!(status, "fail" | "abort" | "panic")
+}
+
+impl Baseline {
+    pub fn from_report(report: &Report) -> Baseline {
+        Baseline {
+            tiles: report
+                .tile_vec
+                .iter()
+                .map(|tile| BaselineTile {
+                    id: tile.id.to_string(),
+                    name: format!("{} > {}", tile.file_path, tile.name),
+                    location: format!("{}[{}]", tile.slab_location, tile.index),
+                    status: status_word(tile.status).to_owned(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> io::Result<Baseline> {
+        json::from_str(&fs::read_to_string(path)?)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, json::to_string_pretty(self)? + "\n")
+    }
+}
+
+/// The differences between a baseline and a later run.
+#[derive(Debug, Default)]
+pub struct Comparison<'b> {
+    pub newly_failing: Vec<&'b BaselineTile>,
+    pub newly_passing: Vec<&'b BaselineTile>,
+    pub appeared: Vec<&'b BaselineTile>,
+    pub disappeared: Vec<&'b BaselineTile>,
+}
+
+impl<'b> Comparison<'b> {
+    pub fn new(before: &'b Baseline, after: &'b Baseline) -> Comparison<'b> {
+        let before_map: HashMap<&str, &BaselineTile> = before
+            .tiles
+            .iter()
+            .map(|tile| (tile.id.as_str(), tile))
+            .collect();
+        let after_map: HashMap<&str, &BaselineTile> = after
+            .tiles
+            .iter()
+            .map(|tile| (tile.id.as_str(), tile))
+            .collect();
+
+        let mut comparison = Comparison::default();
+        for tile in after.tiles.iter() {
+            match before_map.get(tile.id.as_str()) {
+                None => comparison.appeared.push(tile),
+                Some(old) if is_passing(&old.status) && !is_passing(&tile.status) => {
+                    comparison.newly_failing.push(tile)
+                }
+                Some(old) if !is_passing(&old.status) && is_passing(&tile.status) => {
+                    comparison.newly_passing.push(tile)
+                }
+                Some(_) => {}
+            }
+        }
+        for tile in before.tiles.iter() {
+            if !after_map.contains_key(tile.id.as_str()) {
+                comparison.disappeared.push(tile);
+            }
+        }
+        comparison
+    }
+
+    pub fn write(&self, baseline_path: &Path, stdout: &mut Writable) -> io::Result<()> {
+        writeln!(
+            stdout,
+            "Compared to the baseline {}:",
+            baseline_path.display()
+        )?;
+        let section_array = [
+            ("Newly failing", &self.newly_failing),
+            ("Newly passing", &self.newly_passing),
+            ("Appeared", &self.appeared),
+            ("Disappeared", &self.disappeared),
+        ];
+        for (title, tile_vec) in section_array {
+            writeln!(stdout, "  {title}: {}", tile_vec.len())?;
+            for tile in tile_vec.iter() {
+                writeln!(
+                    stdout,
+                    "    {} #{} {} ({})",
+                    tile.status.to_uppercase(),
+                    tile.id,
+                    tile.name,
+                    tile.location
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(id: &str, status: &str) -> BaselineTile {
+        BaselineTile {
+            id: id.to_owned(),
+            name: format!("a.yaml > {id}"),
+            location: "a.yaml:1:1[0]".to_owned(),
+            status: status.to_owned(),
+        }
+    }
+
+    #[test]
+    fn it_compares_the_tiles_by_id() {
+        let before = Baseline {
+            tiles: vec![tile("a", "pass"), tile("b", "fail"), tile("c", "pass")],
+        };
+        let after = Baseline {
+            tiles: vec![tile("a", "fail"), tile("b", "pass"), tile("d", "pass")],
+        };
+        let comparison = Comparison::new(&before, &after);
+
+        let ids = |tile_vec: &Vec<&BaselineTile>| -> Vec<String> {
+            tile_vec.iter().map(|tile| tile.id.clone()).collect()
+        };
+        assert_eq!(ids(&comparison.newly_failing), ["a"]);
+        assert_eq!(ids(&comparison.newly_passing), ["b"]);
+        assert_eq!(ids(&comparison.appeared), ["d"]);
+        assert_eq!(ids(&comparison.disappeared), ["c"]);
+    }
+}
@@ -0,0 +1,206 @@
+//! The breakdown of the outcome of a run, by spec file and by test box. Each
+//! table is only printed when it has more than one row, since a single row
+//! would repeat the summary line.
+
+use crate::report::Report;
+use crate::report::SkipReport;
+use crate::report::TileReport;
+use crate::FailStatus;
+use specimen__writable::Writable;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+
+/// The name used for the tiles and nodules which have no `box` key.
+const NO_BOX: &str = "(no box)";
+
+const COLUMN_NAME_ARRAY: [&str; 8] = [
+    "Passed",
+    "Failed",
+    "Aborted",
+    "Panicked",
+    "XFailed",
+    "XPassed",
+    "Pending nodes",
+    "Duration",
+];
+
+/// The columns of the tiles with the XFAIL flag, which are only printed
+/// when the run has such tiles
+const XFAIL_COLUMN_NAME_ARRAY: [&str; 2] = ["XFailed", "XPassed"];
+
+#[derive(Default)]
+struct Group {
+    name: Box<str>,
+    passed: usize,
+    failed: usize,
+    aborted: usize,
+    panicked: usize,
+    xfailed: usize,
+    xpassed: usize,
+    /// The number of pending nodules, whose tiles are neither counted nor run
+    pending_node_count: usize,
+    duration: Duration,
+}
+
+/// returns the group of the given name, creating it if needed. The groups
+/// are kept in the order in which they are first encountered.
+fn get_group<'g>(group_vec: &'g mut Vec<Group>, name: &str) -> &'g mut Group {
+    let position = match group_vec.iter().position(|group| &*group.name == name) {
+        Some(position) => position,
+        None => {
+            group_vec.push(Group {
+                name: name.into(),
+                ..Group::default()
+            });
+            group_vec.len() - 1
+        }
+    };
+    &mut group_vec[position]
+}
+
+fn group_by(
+    report: &Report,
+    tile_key: impl Fn(&TileReport) -> Box<str>,
+    skip_key: impl Fn(&SkipReport) -> Box<str>,
+) -> Vec<Group> {
+    let mut group_vec = Vec::new();
+    for tile in report.tile_vec.iter() {
+        let group = get_group(&mut group_vec, &tile_key(tile));
+        match tile.status {
+            FailStatus::Pristine => group.passed += 1,
+            FailStatus::Failed => group.failed += 1,
+            FailStatus::Aborted => group.aborted += 1,
+            FailStatus::Panicked => group.panicked += 1,
+            FailStatus::ExpectedFailure => group.xfailed += 1,
+            FailStatus::UnexpectedPass => group.xpassed += 1,
+        }
+        group.duration += tile.duration;
+    }
+    for skip in report.skip_vec.iter() {
+        get_group(&mut group_vec, &skip_key(skip)).pending_node_count += 1;
+    }
+    group_vec
+}
+
+fn write_table(
+    title: &str,
+    group_vec: &[Group],
+    with_xfail: bool,
+    stdout: &mut Writable,
+) -> io::Result<()> {
+    let is_shown =
+        |column_name: &str| with_xfail || !XFAIL_COLUMN_NAME_ARRAY.contains(&column_name);
+
+    let width = group_vec
+        .iter()
+        .map(|group| group.name.chars().count())
+        .chain([title.len()])
+        .max()
+        .unwrap_or_default();
+
+    write!(stdout, "{title:width$}")?;
+    for column_name in COLUMN_NAME_ARRAY {
+        if is_shown(column_name) {
+            write!(stdout, "  {column_name}")?;
+        }
+    }
+    writeln!(stdout)?;
+
+    for group in group_vec {
+        let value_array = [
+            group.passed.to_string(),
+            group.failed.to_string(),
+            group.aborted.to_string(),
+            group.panicked.to_string(),
+            group.xfailed.to_string(),
+            group.xpassed.to_string(),
+            group.pending_node_count.to_string(),
+            format!("{}ms", group.duration.as_millis()),
+        ];
+        write!(stdout, "{:width$}", group.name)?;
+        for (column_name, value) in COLUMN_NAME_ARRAY.iter().zip(value_array) {
+            if is_shown(column_name) {
+                write!(stdout, "  {value:>0$}", column_name.len())?;
+            }
+        }
+        writeln!(stdout)?;
+    }
+    Ok(())
+}
+
+pub fn write_breakdown(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    let file_group_vec = group_by(
+        report,
+        |tile| tile.file_path.as_ref().into(),
+        |skip| skip.file_path.as_ref().into(),
+    );
+    let box_group_vec = group_by(
+        report,
+        |tile| tile.box_name().unwrap_or(NO_BOX).into(),
+        |skip| skip.box_name.clone().unwrap_or_else(|| NO_BOX.into()),
+    );
+
+    let with_xfail = report.tile_vec.iter().any(|tile| {
+        matches!(
+            tile.status,
+            FailStatus::ExpectedFailure | FailStatus::UnexpectedPass
+        )
+    });
+    if file_group_vec.len() > 1 {
+        write_table("File", &file_group_vec, with_xfail, stdout)?;
+    }
+    if box_group_vec.len() > 1 {
+        write_table("Box", &box_group_vec, with_xfail, stdout)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(file_path: &str, box_name: &str, status: FailStatus) -> TileReport {
+        let mut tile = TileReport {
+            duration: Duration::from_millis(2),
+            ..TileReport::new(file_path, 0, status)
+        };
+        tile.data.insert("box".into(), box_name.into());
+        tile
+    }
+
+    #[test]
+    fn it_groups_the_tiles_by_file_and_by_box() {
+        let report = Report {
+            tile_vec: vec![
+                tile("a.yaml", "zoo", FailStatus::Pristine),
+                tile("a.yaml", "zoo", FailStatus::Failed),
+                tile("b.yaml", "zoo", FailStatus::Aborted),
+            ],
+            skip_vec: vec![SkipReport {
+                file_path: "b.yaml".into(),
+                location: "b.yaml:3:5".into(),
+                box_name: None,
+                reason: None,
+                until: None,
+                expired: false,
+            }],
+            ..Report::default()
+        };
+        let mut stdout = Writable::Vec(Vec::new());
+        write_breakdown(&report, &mut stdout).unwrap();
+
+        let Writable::Vec(buffer) = stdout else {
+            unreachable!()
+        };
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "File    Passed  Failed  Aborted  Panicked  Pending nodes  Duration\n\
+            a.yaml       1       1        0         0              0       4ms\n\
+            b.yaml       0       0        1         0              1       2ms\n\
+            Box       Passed  Failed  Aborted  Panicked  Pending nodes  Duration\n\
+            zoo            1       1        1         0              0       6ms\n\
+            (no box)       0       0        0         0              1       0ms\n"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Conditions restrict the tiles of a nodule to some environments. They are
+//! set with the `when` key, e.g. `when: {env: DB_URL, os: linux}`, and
+//! inherited by the descendants. The tiles of a slab run only if all its
+//! conditions hold; otherwise they are skipped and the reason is reported.
+//!
+//! - `env`: environment variables which must be set and not empty, or
+//!   `NAME=value` to require a value
+//! - `os`: the operating systems the tiles can run on, as in
+//!   `std::env::consts::OS`, e.g. `linux`
+//! - `arch`: the architectures the tiles can run on, as in
+//!   `std::env::consts::ARCH`, e.g. `x86_64`
+//! - `predicate`: the names of predicates registered in
+//!   `Options::predicate_map`, which must return true
+//!
+//! Each key accepts a string or a sequence of strings.
+
+use specimen__yaml as yaml;
+use std::collections::HashMap;
+use std::env;
+
+/// The predicates which can be named by the `predicate` condition
+pub type PredicateMap = HashMap<Box<str>, fn() -> bool>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// The variable must be set and not empty, or equal to the value if one
+    /// is given
+    Env {
+        name: Box<str>,
+        value: Option<Box<str>>,
+    },
+    /// The OS must be one of these
+    Os(Vec<Box<str>>),
+    /// The architecture must be one of these
+    Arch(Vec<Box<str>>),
+    Predicate(Box<str>),
+}
+
+impl Condition {
+    /// Reads the conditions of the value of a `when` key
+    pub fn parse(node: &yaml::Yaml) -> Result<Vec<Condition>, String> {
+        let mapping = match node.data {
+            yaml::YamlData::Mapping(ref mapping) => mapping,
+            _ => return Err("the when key must be a mapping".into()),
+        };
+        let mut condition_vec = Vec::new();
+        for (key, value) in mapping.iter() {
+            let key = match key.data {
+                yaml::YamlData::String(ref key) => key.as_str(),
+                _ => return Err("the keys of the when mapping must be strings".into()),
+            };
+            let word_vec = read_words(key, value)?;
+            match key {
+                "env" => {
+                    condition_vec.extend(word_vec.into_iter().map(
+                        |word| match word.split_once('=') {
+                            Some((name, value)) => Condition::Env {
+                                name: name.into(),
+                                value: Some(value.into()),
+                            },
+                            None => Condition::Env {
+                                name: word,
+                                value: None,
+                            },
+                        },
+                    ))
+                }
+                "os" => condition_vec.push(Condition::Os(word_vec)),
+                "arch" => condition_vec.push(Condition::Arch(word_vec)),
+                "predicate" => condition_vec.extend(word_vec.into_iter().map(Condition::Predicate)),
+                _ => {
+                    return Err(format!(
+                    "unknown condition \"{key}\", the conditions are env, os, arch and predicate"
+                ))
+                }
+            }
+        }
+        Ok(condition_vec)
+    }
+
+    /// returns the reason why the condition does not hold, if it does not
+    pub fn check(&self, predicate_map: &PredicateMap) -> Result<(), String> {
+        match self {
+            Condition::Env { name, value: None } => match env::var(&**name) {
+                Ok(actual) if !actual.is_empty() => Ok(()),
+                _ => Err(format!("the environment variable {name} is not set")),
+            },
+            Condition::Env {
+                name,
+                value: Some(value),
+            } => match env::var(&**name) {
+                Ok(actual) if actual == **value => Ok(()),
+                Ok(actual) => Err(format!(
+                    "the environment variable {name} is \"{actual}\", not \"{value}\""
+                )),
+                Err(_) => Err(format!("the environment variable {name} is not set")),
+            },
+            Condition::Os(os_vec) => check_one_of("OS", env::consts::OS, os_vec),
+            Condition::Arch(arch_vec) => check_one_of("architecture", env::consts::ARCH, arch_vec),
+            Condition::Predicate(name) => match predicate_map.get(name) {
+                Some(predicate) if predicate() => Ok(()),
+                Some(_) => Err(format!("the predicate {name} is false")),
+                None => Err(format!("no predicate named {name} is registered")),
+            },
+        }
+    }
+}
+
+/// returns the reason why the first condition which does not hold fails, if
+/// any
+pub fn check_all(
+    condition_slice: &[Condition],
+    predicate_map: &PredicateMap,
+) -> Result<(), String> {
+    condition_slice
+        .iter()
+        .try_for_each(|condition| condition.check(predicate_map))
+}
+
+fn check_one_of(what: &str, actual: &str, expected_slice: &[Box<str>]) -> Result<(), String> {
+    if expected_slice.iter().any(|expected| **expected == *actual) {
+        return Ok(());
+    }
+    Err(format!(
+        "the {what} is {actual}, not {}",
+        expected_slice.join(" or ")
+    ))
+}
+
+fn read_words(key: &str, node: &yaml::Yaml) -> Result<Vec<Box<str>>, String> {
+    let error = || format!("the {key} condition must be a string or a sequence of strings");
+    match node.data {
+        yaml::YamlData::String(ref word) => Ok(vec![word.as_str().into()]),
+        yaml::YamlData::List(ref word_list) => word_list
+            .iter()
+            .map(|word_node| match word_node.data {
+                yaml::YamlData::String(ref word) => Ok(word.as_str().into()),
+                _ => Err(error()),
+            })
+            .collect(),
+        _ => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Vec<Condition>, String> {
+        Condition::parse(&yaml::YamlLoader::load_from_str(text).unwrap().remove(0))
+    }
+
+    #[test]
+    fn it_reads_the_conditions() {
+        assert_eq!(
+            parse("{env: [DB_URL, MODE=fast], os: linux, predicate: docker}").unwrap(),
+            [
+                Condition::Env {
+                    name: "DB_URL".into(),
+                    value: None
+                },
+                Condition::Env {
+                    name: "MODE".into(),
+                    value: Some("fast".into())
+                },
+                Condition::Os(vec!["linux".into()]),
+                Condition::Predicate("docker".into()),
+            ]
+        );
+        assert!(parse("{platform: linux}").is_err());
+        assert!(parse("linux").is_err());
+    }
+
+    #[test]
+    fn it_gives_the_reason_of_unmet_conditions() {
+        let mut predicate_map = PredicateMap::new();
+        predicate_map.insert("never".into(), || false);
+        let check = |text: &str| check_all(&parse(text).unwrap(), &predicate_map);
+
+        assert_eq!(
+            check(&format!("{{os: [other, {}]}}", env::consts::OS)),
+            Ok(())
+        );
+        assert_eq!(
+            check("{arch: [other]}"),
+            Err(format!(
+                "the architecture is {}, not other",
+                env::consts::ARCH
+            ))
+        );
+        assert_eq!(
+            check("{predicate: never}"),
+            Err("the predicate never is false".into())
+        );
+        assert_eq!(
+            check("{predicate: unknown}"),
+            Err("no predicate named unknown is registered".into())
+        );
+    }
+}
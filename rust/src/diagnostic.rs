@@ -0,0 +1,87 @@
+//! The diagnostics are the warnings and errors issued while parsing the spec
+//! files and selecting the slabs. They are gathered rather than printed, so
+//! that they can be rendered by the selected reporter and returned to the
+//! caller of the run.
+
+use specimen__yaml as yaml;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    /// An error makes the run fail
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: Box<str>,
+    /// The path of the spec file, or the name of the option for the
+    /// diagnostics about the options
+    pub file_path: Box<str>,
+    /// The line and the column are both 1-based. They are 0 for the
+    /// diagnostics about the options, which have no position.
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Diagnostic {
+    /// The column of the YAML position is 0-based, and is converted here
+    pub fn warning(file_path: &str, position: yaml::Position, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            file_path: file_path.into(),
+            line: position.line,
+            column: position.column + 1,
+        }
+    }
+
+    pub fn error(file_path: &str, position: yaml::Position, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            ..Diagnostic::warning(file_path, position, message)
+        }
+    }
+
+    /// returns a warning about the option of the given name, e.g. an
+    /// environment variable
+    pub fn option_warning(name: &str, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            file_path: name.into(),
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub fn option_error(name: &str, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            ..Diagnostic::option_warning(name, message)
+        }
+    }
+
+    /// returns the location of the diagnostic, as `path:line:column`, or the
+    /// name of the option for the diagnostics about the options
+    pub fn location(&self) -> String {
+        if self.line == 0 {
+            return self.file_path.to_string();
+        }
+        format!("{}:{}:{}", self.file_path, self.line, self.column)
+    }
+}
+
+/// Writes the diagnostic as `Warning(path:line:column): message`, or
+/// `Error(...)` for errors.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let word = match self.severity {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        write!(f, "{}({}): {}", word, self.location(), self.message)
+    }
+}
@@ -0,0 +1,317 @@
+//! Renders the difference between an expected and an actual value, for the
+//! failure messages of the test boxes.
+//!
+//! - Multi-line values are shown as a unified line diff.
+//! - Single-line values are shown with the changed characters highlighted,
+//!   `[-removed-]` and `{+added+}`.
+//! - When the values only differ by their whitespace, the whitespace is made
+//!   visible: `·` for a space, `→` for a tab, `␍` for a carriage return and
+//!   `↵` for a line feed.
+
+/// The number of unchanged lines displayed around each change.
+const CONTEXT_SIZE: usize = 3;
+
+/// The largest table of common subsequences computed by the diff. Beyond it,
+/// the differing parts are shown whole, one after the other.
+const MAX_TABLE_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op<T> {
+    Equal(T),
+    Delete(T),
+    Insert(T),
+}
+
+/// Compares the two values and returns an error describing their
+/// difference if they are not equal. The context, if not empty, is added in
+/// front of the message.
+///
+/// ```
+/// let result = specimen::expect_equal("a\nb\n", "a\nc\n", "page");
+/// assert!(result.unwrap_err().contains("-c\n+b"));
+/// ```
+pub fn expect_equal(actual: &str, expected: &str, context: &str) -> Result<(), Box<str>> {
+    if actual == expected {
+        return Ok(());
+    }
+    let mut message = String::new();
+    if !context.is_empty() {
+        message.push_str(&format!("({context}): "));
+    }
+    message.push_str(&render(actual, expected));
+    Err(message.into())
+}
+
+/// Renders the difference between the two values.
+pub fn render(actual: &str, expected: &str) -> String {
+    let whitespace_only = expected.split_whitespace().eq(actual.split_whitespace());
+    let is_multiline = expected.trim_end_matches('\n').contains('\n')
+        || actual.trim_end_matches('\n').contains('\n');
+
+    let mut message = String::new();
+    if whitespace_only {
+        message.push_str("whitespace-only difference, ");
+    }
+    if is_multiline {
+        message.push_str("expected (-) and actual (+) differ:\n");
+        message.push_str(&render_lines(expected, actual, whitespace_only));
+    } else {
+        let (expected, actual) = if whitespace_only {
+            (show_whitespace(expected), show_whitespace(actual))
+        } else {
+            (expected.to_owned(), actual.to_owned())
+        };
+        message.push_str("expected [-] and actual {+} differ:\n");
+        message.push_str(&render_chars(&expected, &actual));
+    }
+    message
+}
+
+fn show_whitespace(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ' ' => '·',
+            '\t' => '→',
+            '\r' => '␍',
+            '\n' => '↵',
+            c => c,
+        })
+        .collect()
+}
+
+/// Computes the shortest edit script turning `a` into `b`, using the table
+/// of the longest common subsequences of their suffixes. The common prefix
+/// and suffix are left out of the table; if the rest is still too large, it
+/// is deleted and inserted whole.
+fn edit_script<T: PartialEq + Copy>(a: &[T], b: &[T]) -> Vec<Op<T>> {
+    let prefix_len = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix_len = a[prefix_len..]
+        .iter()
+        .rev()
+        .zip(b[prefix_len..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let mut op_vec: Vec<Op<T>> = a[..prefix_len].iter().map(|x| Op::Equal(*x)).collect();
+    let a_middle = &a[prefix_len..a.len() - suffix_len];
+    let b_middle = &b[prefix_len..b.len() - suffix_len];
+    if a_middle.len().saturating_mul(b_middle.len()) > MAX_TABLE_SIZE {
+        op_vec.extend(a_middle.iter().map(|x| Op::Delete(*x)));
+        op_vec.extend(b_middle.iter().map(|y| Op::Insert(*y)));
+    } else {
+        op_vec.extend(lcs_edit_script(a_middle, b_middle));
+    }
+    op_vec.extend(a[a.len() - suffix_len..].iter().map(|x| Op::Equal(*x)));
+    op_vec
+}
+
+fn lcs_edit_script<T: PartialEq + Copy>(a: &[T], b: &[T]) -> Vec<Op<T>> {
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut op_vec = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            op_vec.push(Op::Equal(a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || table[i + 1][j] >= table[i][j + 1]) {
+            op_vec.push(Op::Delete(a[i]));
+            i += 1;
+        } else {
+            op_vec.push(Op::Insert(b[j]));
+            j += 1;
+        }
+    }
+    op_vec
+}
+
+fn render_lines(expected: &str, actual: &str, whitespace_only: bool) -> String {
+    let (expected, actual) = if whitespace_only {
+        // Keep the line endings, so that they can be made visible
+        (
+            expected
+                .split_inclusive('\n')
+                .map(show_whitespace)
+                .collect::<Vec<_>>(),
+            actual
+                .split_inclusive('\n')
+                .map(show_whitespace)
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        (
+            expected.lines().map(str::to_owned).collect(),
+            actual.lines().map(str::to_owned).collect(),
+        )
+    };
+    let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+    let actual: Vec<&str> = actual.iter().map(String::as_str).collect();
+    let op_vec = edit_script(&expected, &actual);
+
+    // Group the operations into hunks, each surrounded by at most
+    // CONTEXT_SIZE unchanged lines
+    let change_index_vec: Vec<usize> = op_vec
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(_)))
+        .map(|(k, _)| k)
+        .collect();
+    let mut hunk_vec: Vec<(usize, usize)> = Vec::new();
+    for k in change_index_vec {
+        let start = k.saturating_sub(CONTEXT_SIZE);
+        let end = (k + CONTEXT_SIZE + 1).min(op_vec.len());
+        match hunk_vec.last_mut() {
+            Some(hunk) if start <= hunk.1 => hunk.1 = end,
+            _ => hunk_vec.push((start, end)),
+        }
+    }
+
+    let mut line_vec = vec!["--- expected".to_owned(), "+++ actual".to_owned()];
+    // The line numbers, in the expected and in the actual value, of the
+    // first operation of the hunk
+    let (mut expected_line, mut actual_line) = (1, 1);
+    let mut position = 0;
+    for (start, end) in hunk_vec {
+        for op in op_vec[position..start].iter() {
+            match op {
+                Op::Equal(_) => {
+                    expected_line += 1;
+                    actual_line += 1;
+                }
+                Op::Delete(_) => expected_line += 1,
+                Op::Insert(_) => actual_line += 1,
+            }
+        }
+        let hunk = &op_vec[start..end];
+        let expected_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let actual_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        line_vec.push(format!(
+            "@@ -{},{} +{},{} @@",
+            expected_line, expected_count, actual_line, actual_count
+        ));
+        for op in hunk {
+            line_vec.push(match op {
+                Op::Equal(line) => format!(" {line}"),
+                Op::Delete(line) => format!("-{line}"),
+                Op::Insert(line) => format!("+{line}"),
+            });
+        }
+        expected_line += expected_count;
+        actual_line += actual_count;
+        position = end;
+    }
+    line_vec.join("\n")
+}
+
+fn render_chars(expected: &str, actual: &str) -> String {
+    let expected: Vec<char> = expected.chars().collect();
+    let actual: Vec<char> = actual.chars().collect();
+
+    let mut text = String::new();
+    let mut open: Option<Op<()>> = None;
+    for op in edit_script(&expected, &actual) {
+        let (kind, c) = match op {
+            Op::Equal(c) => (Op::Equal(()), c),
+            Op::Delete(c) => (Op::Delete(()), c),
+            Op::Insert(c) => (Op::Insert(()), c),
+        };
+        if open != Some(kind) {
+            match open {
+                Some(Op::Delete(())) => text.push_str("-]"),
+                Some(Op::Insert(())) => text.push_str("+}"),
+                _ => {}
+            }
+            match kind {
+                Op::Delete(()) => text.push_str("[-"),
+                Op::Insert(()) => text.push_str("{+"),
+                Op::Equal(()) => {}
+            }
+            open = Some(kind);
+        }
+        text.push(c);
+    }
+    match open {
+        Some(Op::Delete(())) => text.push_str("-]"),
+        Some(Op::Insert(())) => text.push_str("+}"),
+        _ => {}
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_multiline_values_as_a_unified_diff() {
+        let expected = "book:\n  left_page: 2\n  size: 10\n";
+        let actual = "book:\n  left_page: 4\n  size: 10\n";
+        assert_eq!(
+            render(actual, expected),
+            "expected (-) and actual (+) differ:\n\
+            --- expected\n\
+            +++ actual\n\
+            @@ -1,3 +1,3 @@\n \
+            book:\n\
+            -  left_page: 2\n\
+            +  left_page: 4\n   \
+            size: 10"
+        );
+    }
+
+    #[test]
+    fn it_highlights_the_changed_characters_of_single_lines() {
+        assert_eq!(
+            render("horse zebu", "horse zebra"),
+            "expected [-] and actual {+} differ:\nhorse zeb[-ra-]{+u+}"
+        );
+    }
+
+    #[test]
+    fn it_makes_whitespace_only_differences_visible() {
+        assert_eq!(
+            render("a  b", "a b"),
+            "whitespace-only difference, expected [-] and actual {+} differ:\na·{+·+}b"
+        );
+        assert!(render("a\nb \n", "a\nb\n").contains("-b↵\n+b·↵"));
+    }
+
+    #[test]
+    fn it_accepts_equal_values() {
+        assert_eq!(expect_equal("a", "a", "context"), Ok(()));
+        assert!(expect_equal("a", "b", "context")
+            .unwrap_err()
+            .starts_with("(context): "));
+    }
+
+    #[test]
+    fn it_shows_large_differences_whole() {
+        let expected: String = (0..2000).map(|k| format!("e{k}\n")).collect();
+        let actual: String = (0..2000).map(|k| format!("a{k}\n")).collect();
+        let message = render(
+            &format!("head\n{actual}tail\n"),
+            &format!("head\n{expected}tail\n"),
+        );
+        let line_vec: Vec<&str> = message.lines().collect();
+        assert_eq!(line_vec[3], "@@ -1,2002 +1,2002 @@");
+        assert_eq!(line_vec[4], " head");
+        assert_eq!(line_vec[5], "-e0");
+        assert_eq!(line_vec[2005], "+a0");
+        assert_eq!(line_vec[4005], " tail");
+    }
+}
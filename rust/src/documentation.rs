@@ -0,0 +1,320 @@
+//! The documentation generator turns the spec files into a Markdown or HTML
+//! document. Each nodule becomes a section, titled with its `name`, which
+//! contains its `about` text, its keys (own and inherited), its matrix axes
+//! and, when the documentation is written by a run, the status of its tiles.
+
+use crate::file;
+use crate::html::escape;
+use crate::nodule::Nodule;
+use crate::report::Report;
+use specimen__focustree as focustree;
+use specimen__yaml as yaml;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Values longer than this are not displayed, only their line count.
+const MAX_VALUE_LENGTH: usize = 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    /// returns the format matching the extension of the path: HTML for
+    /// `.html` and `.htm`, Markdown otherwise.
+    pub fn from_path(path: &Path) -> DocFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("html" | "htm") => DocFormat::Html,
+            _ => DocFormat::Markdown,
+        }
+    }
+}
+
+/// A section of the documentation, corresponding to a nodule.
+struct Section {
+    depth: usize,
+    title: String,
+    about: Option<String>,
+    location: Box<str>,
+    /// `(key, value, inherited)`
+    key_vec: Vec<(String, String, bool)>,
+    /// `(key, values)`
+    axis_vec: Vec<(String, Vec<String>)>,
+    status: Option<String>,
+}
+
+/// Generates the documentation of the given spec files.
+pub fn generate(file_slice: &[file::File], format: DocFormat) -> String {
+    let mut document_store = Vec::from_iter(file_slice.iter().map(|_| Vec::new()));
+    let mut diagnostic_vec = Vec::new();
+    let root = Nodule::parse_root(file_slice, &mut document_store, &mut diagnostic_vec);
+    render(&root, format, None)
+}
+
+/// Renders the `about` value: strings are kept as they are, sequences and
+/// mappings become lists.
+fn about_text(node: &yaml::Yaml) -> Option<String> {
+    let text = match node.data {
+        yaml::YamlData::String(ref text) | yaml::YamlData::Real(ref text) => text.clone(),
+        yaml::YamlData::Integer(number) => number.to_string(),
+        yaml::YamlData::Boolean(value) => value.to_string(),
+        yaml::YamlData::List(ref list) => list
+            .iter()
+            .filter_map(about_text)
+            .map(|item| format!("- {}", item.replace('\n', "\n  ")))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        yaml::YamlData::Mapping(ref mapping) => mapping
+            .iter()
+            .filter_map(|(key, value)| {
+                let key = about_text(key)?;
+                Some(match about_text(value) {
+                    Some(value) => format!("- {key}: {}", value.replace('\n', "\n  ")),
+                    None => format!("- {key}"),
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return None,
+    };
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn display_value(value: &str) -> String {
+    let line_count = value.trim_end_matches('\n').lines().count();
+    if line_count > 1 || value.len() > MAX_VALUE_LENGTH {
+        format!("({line_count} line(s))")
+    } else {
+        value.to_owned()
+    }
+}
+
+fn slab_status(report: &Report, location: &str) -> Option<String> {
+    let tile_vec: Vec<_> = report
+        .tile_vec
+        .iter()
+        .filter(|tile| &*tile.slab_location == location)
+        .collect();
+    if tile_vec.is_empty() {
+        return None;
+    }
+    let passed = tile_vec
+        .iter()
+        .filter(|tile| !tile.status.is_failure())
+        .count();
+    let word = if passed == tile_vec.len() {
+        "PASS"
+    } else {
+        "FAIL"
+    };
+    Some(format!("{word} ({passed}/{} tiles passed)", tile_vec.len()))
+}
+
+fn collect_sections(
+    nodule: &Nodule,
+    depth: usize,
+    report: Option<&Report>,
+    section_vec: &mut Vec<Section>,
+) {
+    let location = nodule.get_location();
+    let mut title = match nodule.node.data["name"].data {
+        yaml::YamlData::String(ref name) => name.clone(),
+        _ => format!("Nodule at line {}", nodule.node.position.line),
+    };
+    if nodule.flag == focustree::Flag::Skip {
+        match &nodule.flag_reason {
+            Some(reason) => title.push_str(&format!(" (pending: {reason})")),
+            None => title.push_str(" (pending)"),
+        }
+    }
+
+    let mut own_key_vec = Vec::new();
+    if let yaml::YamlData::Mapping(ref mapping) = nodule.node.data {
+        for (key, _) in mapping.iter() {
+            if let yaml::YamlData::String(ref key) = key.data {
+                own_key_vec.push(key.as_str());
+            }
+        }
+    }
+
+    let mut key_vec = Vec::new();
+    let mut axis_vec = Vec::new();
+    for (key, value_array) in nodule.data_matrix.0.iter() {
+        if &**key == "filepath" || &**key == "name" {
+            continue;
+        }
+        let inherited = !own_key_vec.contains(&&**key);
+        if value_array.len() > 1 {
+            axis_vec.push((
+                key.to_string(),
+                value_array
+                    .iter()
+                    .map(|value| display_value(value))
+                    .collect(),
+            ));
+        } else if let Some(value) = value_array.first() {
+            key_vec.push((key.to_string(), display_value(value), inherited));
+        }
+    }
+
+    section_vec.push(Section {
+        depth,
+        title,
+        about: about_text(&nodule.node.data["about"]),
+        status: report.and_then(|report| slab_status(report, &location)),
+        location,
+        key_vec,
+        axis_vec,
+    });
+
+    for child in nodule.children.iter() {
+        collect_sections(child, depth + 1, report, section_vec);
+    }
+}
+
+fn render_markdown(file_path: &str, section_vec: &[Section], text: &mut String) {
+    let _ = writeln!(text, "# {file_path}\n");
+    for section in section_vec {
+        let _ = writeln!(
+            text,
+            "{} {}\n",
+            "#".repeat((section.depth + 1).min(6)),
+            section.title
+        );
+        if let Some(about) = &section.about {
+            let _ = writeln!(text, "{about}\n");
+        }
+        let _ = writeln!(text, "- Location: `{}`", section.location);
+        if !section.key_vec.is_empty() {
+            let key_list = section
+                .key_vec
+                .iter()
+                .map(|(key, value, inherited)| {
+                    let suffix = if *inherited { " (inherited)" } else { "" };
+                    format!("`{key}: {value}`{suffix}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(text, "- Keys: {key_list}");
+        }
+        for (key, value_vec) in section.axis_vec.iter() {
+            let _ = writeln!(
+                text,
+                "- Matrix: `{key}` × {} (`{}`)",
+                value_vec.len(),
+                value_vec.join("`, `")
+            );
+        }
+        if let Some(status) = &section.status {
+            let _ = writeln!(text, "- Status: {status}");
+        }
+        text.push('\n');
+    }
+}
+
+fn render_html(file_path: &str, section_vec: &[Section], text: &mut String) {
+    let _ = writeln!(text, "<h1>{}</h1>", escape(file_path));
+    for section in section_vec {
+        let level = (section.depth + 1).min(6);
+        let _ = writeln!(text, "<h{level}>{}</h{level}>", escape(&section.title));
+        if let Some(about) = &section.about {
+            let _ = writeln!(
+                text,
+                "<p style=\"white-space: pre-wrap\">{}</p>",
+                escape(about)
+            );
+        }
+        let _ = writeln!(
+            text,
+            "<ul>\n<li>Location: <code>{}</code></li>",
+            escape(&section.location)
+        );
+        if !section.key_vec.is_empty() {
+            let key_list = section
+                .key_vec
+                .iter()
+                .map(|(key, value, inherited)| {
+                    let suffix = if *inherited { " (inherited)" } else { "" };
+                    format!("<code>{}: {}</code>{suffix}", escape(key), escape(value))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(text, "<li>Keys: {key_list}</li>");
+        }
+        for (key, value_vec) in section.axis_vec.iter() {
+            let _ = writeln!(
+                text,
+                "<li>Matrix: <code>{}</code> × {} (<code>{}</code>)</li>",
+                escape(key),
+                value_vec.len(),
+                escape(&value_vec.join(", "))
+            );
+        }
+        if let Some(status) = &section.status {
+            let _ = writeln!(text, "<li>Status: {}</li>", escape(status));
+        }
+        text.push_str("</ul>\n");
+    }
+}
+
+/// Renders the documentation of the tree whose root is given. The status
+/// of the slabs is included if a report is given.
+pub fn render(root: &Nodule, format: DocFormat, report: Option<&Report>) -> String {
+    let mut text = String::new();
+    if format == DocFormat::Html {
+        text.push_str(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>Specimen documentation</title>\n</head>\n<body>\n",
+        );
+    }
+    for document in root.children.iter() {
+        let mut section_vec = Vec::new();
+        collect_sections(document, 1, report, &mut section_vec);
+        match format {
+            DocFormat::Markdown => render_markdown(&document.file_path, &section_vec, &mut text),
+            DocFormat::Html => render_html(&document.file_path, &section_vec, &mut text),
+        }
+    }
+    if format == DocFormat::Html {
+        text.push_str("</body>\n</html>\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_documents_the_nodule_hierarchy() {
+        let file = file::File {
+            path: "zoo.yaml".into(),
+            content: "name: zoo\n\
+                about: Animals join the zoo one after the other.\n\
+                box: zoo\n\
+                content:\n  \
+                  - name: animal matrix\n    \
+                    animal: [mouse, cat]\n"
+                .into(),
+        };
+        assert_eq!(
+            generate(&[file], DocFormat::Markdown),
+            "# zoo.yaml\n\n\
+            ## zoo\n\n\
+            Animals join the zoo one after the other.\n\n\
+            - Location: `zoo.yaml:1:4`\n\
+            - Keys: `box: zoo`\n\n\
+            ### animal matrix\n\n\
+            - Location: `zoo.yaml:5:8`\n\
+            - Keys: `box: zoo` (inherited)\n\
+            - Matrix: `animal` × 2 (`mouse`, `cat`)\n\n"
+        );
+    }
+}
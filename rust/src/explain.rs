@@ -0,0 +1,127 @@
+//! The explain mode runs no box. It prints each nodule with the reason why
+//! it is selected or dropped, followed by the tiles of the selected slabs,
+//! with the reason why they would run or not.
+
+use crate::nodule::Nodule;
+use crate::options::Options;
+use crate::report::Report;
+use crate::PlannedTile;
+use specimen__focustree as focustree;
+use specimen__focustree::Selection;
+use specimen__writable::Writable;
+use std::io;
+use std::io::Write;
+
+/// returns the reason why a nodule is selected or dropped
+fn describe_selection(nodule: &Nodule, selection: Selection) -> String {
+    match selection {
+        Selection::Selected => "selected".into(),
+        Selection::Skipped if nodule.flag != focustree::Flag::Skip => {
+            "dropped, inside a pending node".into()
+        }
+        Selection::Skipped => match &nodule.flag_reason {
+            Some(reason) => format!("dropped, pending: {reason}"),
+            None => "dropped, pending".into(),
+        },
+        Selection::OutsideFocus => "dropped, outside the focus".into(),
+        Selection::ContainsFocus => "partly selected, contains focused nodes".into(),
+        Selection::FocusIgnored => {
+            "partly selected, its focus is ignored because of focused descendants".into()
+        }
+    }
+}
+
+/// returns the reason why a tile of a selected slab would not run, if any
+fn drop_reason(planned: &PlannedTile, options: &Options) -> Option<&'static str> {
+    if let Some(tag_expression) = &options.tag_expression {
+        if !tag_expression.matches(&planned.slab.tag_vec) {
+            return Some("dropped, excluded by the tags");
+        }
+    }
+    if let Some(filter) = &options.filter {
+        if !planned.matches(filter) {
+            return Some("dropped, filtered out");
+        }
+    }
+    None
+}
+
+/// Writes the nodules of the tree with their selection, and the tiles of
+/// the selected slabs.
+pub fn write_explanation(
+    root: &Nodule,
+    planned_slice: &[PlannedTile],
+    report: &Report,
+    options: &Options,
+    stdout: &mut Writable,
+) -> io::Result<()> {
+    writeln!(stdout, "Explaining the selection, no box is run")?;
+    let mut selected_count = 0;
+    // The first value is the root, which holds the files
+    for (nodule, selection) in focustree::explain_selection(root).into_iter().skip(1) {
+        let indent = "  ".repeat(nodule.tree_path.len() - 1);
+        // The last segment of the tree path is the name of the nodule, or its
+        // position among its siblings
+        let label = format!(
+            "{} ({})",
+            nodule.get_location(),
+            nodule.tree_path.last().map_or("", |segment| segment)
+        );
+
+        let location = nodule.get_location();
+        let condition_skip = report
+            .condition_skip_vec
+            .iter()
+            .find(|skip| nodule.is_leaf && skip.location == location);
+        if let (Selection::Selected, Some(skip)) = (selection, condition_skip) {
+            writeln!(
+                stdout,
+                "{indent}{label}: dropped, its condition does not hold: {}",
+                skip.reason
+            )?;
+            continue;
+        }
+        writeln!(
+            stdout,
+            "{indent}{label}: {}",
+            describe_selection(&nodule, selection)
+        )?;
+
+        if selection != Selection::Selected || !nodule.is_leaf {
+            continue;
+        }
+        for planned in planned_slice
+            .iter()
+            .filter(|planned| std::ptr::eq(planned.slab.node, nodule.node))
+        {
+            let reason = match drop_reason(planned, options) {
+                Some(reason) => reason,
+                None => {
+                    selected_count += 1;
+                    "selected"
+                }
+            };
+            writeln!(
+                stdout,
+                "{indent}  [{}] #{} {}: {reason}",
+                planned.index, planned.id, planned.name
+            )?;
+        }
+    }
+    // The tiles left out of the samples are not planned
+    let unsampled_count: usize = report
+        .sample_vec
+        .iter()
+        .map(|sample| sample.total_count - sample.index_vec.len())
+        .sum();
+    let condition_skip_count: usize = report
+        .condition_skip_vec
+        .iter()
+        .map(|skip| skip.tile_count)
+        .sum();
+    writeln!(
+        stdout,
+        "{selected_count} of {} tile(s) would run",
+        planned_slice.len() + unsampled_count + condition_skip_count
+    )
+}
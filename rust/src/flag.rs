@@ -1,8 +1,122 @@
+use crate::diagnostic::Diagnostic;
 use specimen__focustree as focustree;
 use specimen__yaml as yaml;
+use std::fmt;
+use std::time::SystemTime;
 
-pub fn read_flag(node: &yaml::Yaml) -> focustree::Flag {
-    let mut flag = focustree::Flag::default();
+/// A calendar date, as written in the `until` attribute of a flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Parses a `YYYY-MM-DD` date. returns None if the date does not exist,
+    /// e.g. `2026-02-31`.
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut part_iter = text.splitn(3, '-');
+        let year = part_iter.next()?.parse().ok()?;
+        let month = part_iter.next()?.parse().ok()?;
+        let day = part_iter.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=month_length(year, month)).contains(&day) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// returns the current date, in UTC.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Date::from_days(i64::try_from(seconds / 86400).unwrap_or_default())
+    }
+
+    /// Converts a number of days since 1970-01-01 to a date of the Gregorian
+    /// calendar (Howard Hinnant's `civil_from_days` algorithm).
+    fn from_days(days: i64) -> Date {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date { year, month, day }
+    }
+}
+
+/// returns the number of days of the month, in the Gregorian calendar
+fn month_length(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The content of the `flag` key: the flag word, along with its attributes,
+/// e.g. `PENDING until=2026-12-01 reason="upstream bug"`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FlagValue {
+    pub flag: focustree::Flag,
+    /// The `reason` attribute
+    pub reason: Option<Box<str>>,
+    /// The `until` attribute
+    pub until: Option<Date>,
+    /// true if the XFAIL flag is set. It combines with the other flags, so
+    /// a pending or focused node can be expected to fail.
+    pub expect_failure: bool,
+}
+
+/// Splits the flag string on spaces, except for the spaces between double
+/// quotes, e.g. `reason="upstream bug"`.
+fn split_words(text: &str) -> Vec<String> {
+    let mut word_vec = Vec::new();
+    let mut word = String::new();
+    let mut in_quotes = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                word.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !word.is_empty() {
+                    word_vec.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        word_vec.push(word);
+    }
+    word_vec
+}
+
+pub fn read_flag(
+    node: &yaml::Yaml,
+    file_path: &str,
+    diagnostic_vec: &mut Vec<Diagnostic>,
+) -> FlagValue {
+    let mut value = FlagValue::default();
     // flag_name is used for printing warning(s) if needed
     let mut flag_name = "";
     let mut both = false;
@@ -12,36 +126,127 @@ pub fn read_flag(node: &yaml::Yaml) -> focustree::Flag {
         _ => panic!("The flag value must be a string."),
     };
 
-    for word in text.split(" ") {
-        match word {
+    let mut warn = |message: String| {
+        diagnostic_vec.push(Diagnostic::warning(file_path, node.position, &message))
+    };
+
+    for word in split_words(text) {
+        if let Some((key, attribute)) = word.split_once('=') {
+            let attribute = attribute.trim_matches('"');
+            match key {
+                "reason" => value.reason = Some(attribute.into()),
+                "until" => match Date::parse(attribute) {
+                    Some(date) => value.until = Some(date),
+                    None => warn(format!(
+                        "The until attribute must be a date such as 2026-12-01, got \"{}\". It has been ignored.",
+                        attribute
+                    )),
+                },
+                _ => warn(format!(
+                    "Unrecognized flag attribute \"{}\". It has been ignored.",
+                    key
+                )),
+            }
+            continue;
+        }
+        match word.as_str() {
             "FOCUS" => {
-                if flag == focustree::Flag::Skip {
+                if value.flag == focustree::Flag::Skip {
                     both = true
                 }
-                flag = focustree::Flag::Focus;
-                flag_name = word;
+                value.flag = focustree::Flag::Focus;
+                flag_name = "FOCUS";
             }
             "PENDING" => {
-                if flag == focustree::Flag::Focus {
+                if value.flag == focustree::Flag::Focus {
                     both = true
                 }
-                flag = focustree::Flag::Skip;
-                flag_name = word;
+                value.flag = focustree::Flag::Skip;
+                flag_name = "PENDING";
             }
+            "XFAIL" => value.expect_failure = true,
             _ => {
                 if word == word.to_uppercase() && word != word.to_lowercase() {
-                    eprintln!(
-                        "Warning: Unrecognized all uppercase flag \"{}\". It has been ignored.",
+                    warn(format!(
+                        "Unrecognized all uppercase flag \"{}\". It has been ignored.",
                         word
-                    )
+                    ))
                 }
             }
         };
     }
 
     if both {
-        eprintln!("Warning: Both FOCUS and PENDING flags have been found among the flags of a node. {} has been kept.", flag_name)
+        warn(format!("Both FOCUS and PENDING flags have been found among the flags of a node. {} has been kept.", flag_name))
     }
 
-    flag
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_the_flag_attributes() {
+        let node = yaml::YamlLoader::load_from_str(
+            "PENDING until=2026-12-01 reason=\"upstream bug\" since=now until=2026-02-31",
+        )
+        .unwrap()
+        .remove(0);
+        let mut diagnostic_vec = Vec::new();
+        assert_eq!(
+            read_flag(&node, "a.yaml", &mut diagnostic_vec),
+            FlagValue {
+                flag: focustree::Flag::Skip,
+                reason: Some("upstream bug".into()),
+                until: Some(Date {
+                    year: 2026,
+                    month: 12,
+                    day: 1
+                }),
+                expect_failure: false,
+            }
+        );
+        assert_eq!(diagnostic_vec.len(), 2);
+        assert!(diagnostic_vec[0].message.contains("\"since\""));
+        assert!(diagnostic_vec[1].message.contains("must be a date"));
+        assert!(Date::parse("2024-02-29").is_some());
+        assert!(Date::parse("2100-02-29").is_none());
+        assert!(Date::parse("2026-04-31").is_none());
+    }
+
+    #[test]
+    fn it_keeps_the_last_of_conflicting_flags() {
+        let node = yaml::YamlLoader::load_from_str("XFAIL PENDING FOCUS")
+            .unwrap()
+            .remove(0);
+        let mut diagnostic_vec = Vec::new();
+        let value = read_flag(&node, "a.yaml", &mut diagnostic_vec);
+        assert_eq!(value.flag, focustree::Flag::Focus);
+        assert!(value.expect_failure);
+        assert_eq!(
+            &*diagnostic_vec[0].message,
+            "Both FOCUS and PENDING flags have been found among the flags of a node. FOCUS has been kept."
+        );
+    }
+
+    #[test]
+    fn it_combines_the_xfail_flag_with_the_others() {
+        let node = yaml::YamlLoader::load_from_str("FOCUS XFAIL")
+            .unwrap()
+            .remove(0);
+        let mut diagnostic_vec = Vec::new();
+        let value = read_flag(&node, "a.yaml", &mut diagnostic_vec);
+        assert_eq!(value.flag, focustree::Flag::Focus);
+        assert!(value.expect_failure);
+        assert!(diagnostic_vec.is_empty());
+    }
+
+    #[test]
+    fn it_computes_dates_from_days() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(20454).to_string(), "2026-01-01");
+        assert_eq!(Date::from_days(11016).to_string(), "2000-02-29");
+    }
 }
@@ -0,0 +1,315 @@
+//! The HTML reporter writes a self-contained HTML file which mirrors the tree
+//! of the spec files and their nodules. Each slab lists its tiles, with their
+//! outcome, data, failure message and duration. The nodules containing a
+//! failure are expanded, the others are collapsed.
+
+use crate::nodule::Nodule;
+use crate::report::Report;
+use crate::report::TileReport;
+use crate::timing::format_duration;
+use crate::FailStatus;
+use specimen__focustree as focustree;
+use specimen__yaml as yaml;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+details { margin-left: 1.5em; }
+summary { cursor: pointer; padding: 2px 0; }
+.location { color: #777; font-family: monospace; font-size: 0.9em; }
+.tile { margin: 4px 0 4px 3em; padding: 4px 8px; border-left: 4px solid #bbb; }
+.pass { border-color: #2a2; }
+.fail, .panic { border-color: #d22; }
+.abort, .xpass { border-color: #d90; }
+.xfail { border-color: #888; }
+.pending, .unselected { color: #999; }
+.word { font-weight: bold; font-family: monospace; }
+table { border-collapse: collapse; font-family: monospace; font-size: 0.9em; }
+td { border: 1px solid #ddd; padding: 1px 6px; vertical-align: top; white-space: pre-wrap; }
+pre { background: #f6f6f6; padding: 6px; white-space: pre-wrap; }
+";
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+struct Writer<'r> {
+    tile_map: HashMap<&'r str, Vec<&'r TileReport>>,
+    html: String,
+}
+
+impl<'r> Writer<'r> {
+    /// returns true if one of the tiles of the nodule or of its descendants
+    /// did not pass.
+    fn has_failure(&self, nodule: &Nodule) -> bool {
+        let location = nodule.get_location();
+        let tile_failed = self
+            .tile_map
+            .get(&*location)
+            .is_some_and(|tile_vec| tile_vec.iter().any(|tile| tile.status.is_failure()));
+        tile_failed || nodule.children.iter().any(|child| self.has_failure(child))
+    }
+
+    fn write_tile(&mut self, tile: &TileReport) {
+        let (word, class) = match tile.status {
+            FailStatus::Pristine => ("PASS", "pass"),
+            FailStatus::Failed => ("FAIL", "fail"),
+            FailStatus::Aborted => ("ABORT", "abort"),
+            FailStatus::Panicked => ("PANIC", "panic"),
+            FailStatus::ExpectedFailure => ("XFAIL", "xfail"),
+            FailStatus::UnexpectedPass => ("XPASS", "xpass"),
+        };
+        let html = &mut self.html;
+        let _ = write!(
+            html,
+            "<div class=\"tile {class}\"><span class=\"word\">{word}</span> [{}] {} \
+            <span class=\"location\">#{} {}</span>",
+            tile.index,
+            escape(&tile.name),
+            escape(&tile.id),
+            format_duration(tile.duration),
+        );
+        if !tile.message.is_empty() {
+            let _ = write!(html, "<pre>{}</pre>", escape(&tile.message));
+        }
+        let mut key_vec: Vec<_> = tile.data.keys().collect();
+        key_vec.sort();
+        html.push_str("<details><summary>data</summary><table>");
+        for key in key_vec {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(key),
+                escape(&tile.data[key])
+            );
+        }
+        html.push_str("</table></details></div>\n");
+    }
+
+    fn write_nodule(&mut self, nodule: &Nodule) {
+        let location = nodule.get_location();
+        let open = if self.has_failure(nodule) {
+            " open"
+        } else {
+            ""
+        };
+        let tile_vec = self.tile_map.remove(&*location).unwrap_or_default();
+
+        let mut label = match nodule.node.data["name"].data {
+            yaml::YamlData::String(ref name) => escape(name),
+            _ => String::new(),
+        };
+        if let yaml::YamlData::String(ref box_name) = nodule.node.data["box"].data {
+            let _ = write!(label, " <i>box: {}</i>", escape(box_name));
+        }
+        let class = if nodule.flag == focustree::Flag::Skip {
+            match &nodule.flag_reason {
+                Some(reason) => {
+                    let _ = write!(label, " (pending: {})", escape(reason));
+                }
+                None => label.push_str(" (pending)"),
+            }
+            " class=\"pending\""
+        } else if nodule.is_leaf && tile_vec.is_empty() {
+            label.push_str(" (not selected)");
+            " class=\"unselected\""
+        } else {
+            ""
+        };
+
+        let _ = writeln!(
+            self.html,
+            "<details{open}><summary{class}>{label} <span class=\"location\">{}</span></summary>",
+            escape(&location),
+        );
+        for tile in tile_vec {
+            self.write_tile(tile);
+        }
+        for child in nodule.children.iter() {
+            self.write_nodule(child);
+        }
+        self.html.push_str("</details>\n");
+    }
+}
+
+/// Renders the report of the run of the tree whose root is given.
+pub fn render(root: &Nodule, report: &Report) -> String {
+    let mut writer = Writer {
+        tile_map: HashMap::new(),
+        html: String::new(),
+    };
+    for tile in report.tile_vec.iter() {
+        writer
+            .tile_map
+            .entry(&tile.slab_location)
+            .or_default()
+            .push(tile);
+    }
+
+    let _ = write!(
+        writer.html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+        <title>Specimen report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+        <h1>Specimen report</h1>\n\
+        <p>{} -- Ran {} tiles in {}: {} passed, {} failed, {} aborted, {} panicked</p>\n",
+        if report.is_success() {
+            "SUCCESS"
+        } else {
+            "FAILURE"
+        },
+        report.tile_vec.len(),
+        format_duration(report.duration),
+        report.count(FailStatus::Pristine),
+        report.count(FailStatus::Failed),
+        report.count(FailStatus::Aborted),
+        report.count(FailStatus::Panicked),
+    );
+
+    if !report.diagnostic_vec.is_empty() {
+        writer.html.push_str("<ul>\n");
+        for diagnostic in report.diagnostic_vec.iter() {
+            let _ = writeln!(writer.html, "<li>{}</li>", escape(&diagnostic.to_string()));
+        }
+        writer.html.push_str("</ul>\n");
+    }
+
+    // The documents are grouped by file
+    let mut position = 0;
+    while position < root.children.len() {
+        let file_path = &root.children[position].file_path;
+        let end = root.children[position..]
+            .iter()
+            .position(|nodule| nodule.file_path != *file_path)
+            .map_or(root.children.len(), |k| position + k);
+        let document_slice = &root.children[position..end];
+
+        let open = if document_slice
+            .iter()
+            .any(|nodule| writer.has_failure(nodule))
+        {
+            " open"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            writer.html,
+            "<details{open}><summary><b>{}</b></summary>",
+            escape(file_path)
+        );
+        for nodule in document_slice {
+            writer.write_nodule(nodule);
+        }
+        writer.html.push_str("</details>\n");
+        position = end;
+    }
+
+    writer.html.push_str("</body>\n</html>\n");
+    writer.html
+}
+
+pub fn write_file(path: &Path, root: &Nodule, report: &Report) -> io::Result<()> {
+    fs::write(path, render(root, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file;
+    use std::time::Duration;
+
+    #[test]
+    fn it_renders_the_tree_with_the_tiles() {
+        let file_slice = [file::File {
+            path: "zoo.yaml".into(),
+            content: "name: zoo\n\
+                content:\n  \
+                  - name: birds\n    \
+                    content:\n      \
+                      - name: parrot\n        \
+                        animal: parrot\n      \
+                      - name: dodo\n        \
+                        flag: PENDING reason=\"extinct\"\n        \
+                        animal: dodo\n  \
+                  - name: horse\n    \
+                    animal: horse\n  \
+                  - name: fish\n    \
+                    animal: fish\n"
+                .into(),
+        }];
+        let mut document_store = vec![Vec::new()];
+        let root = Nodule::parse_root(&file_slice, &mut document_store, &mut Vec::new());
+        let zoo = &root.children[0];
+
+        let mut parrot = TileReport {
+            slab_location: zoo.children[0].children[0].get_location(),
+            name: "zoo > birds > parrot".into(),
+            id: "a1b2".into(),
+            message: "The <parrot> flew away".into(),
+            duration: Duration::from_millis(3),
+            ..TileReport::new("zoo.yaml", 0, FailStatus::Failed)
+        };
+        parrot.data.insert("animal".into(), "parrot".into());
+        let horse = TileReport {
+            slab_location: zoo.children[1].get_location(),
+            ..TileReport::new("zoo.yaml", 0, FailStatus::Pristine)
+        };
+        let report = Report {
+            tile_vec: vec![parrot, horse],
+            ..Report::default()
+        };
+
+        let html = render(&root, &report);
+        let body = &html[html.find("<p>").unwrap()..];
+        assert_eq!(
+            body,
+            "<p>FAILURE -- Ran 2 tiles in 0.0ms: 1 passed, 1 failed, 0 aborted, 0 panicked</p>\n\
+            <details open><summary><b>zoo.yaml</b></summary>\n\
+            <details open><summary>zoo <span class=\"location\">zoo.yaml:1:4</span></summary>\n\
+            <details open><summary>birds <span class=\"location\">zoo.yaml:3:8</span></summary>\n\
+            <details open><summary>parrot <span class=\"location\">zoo.yaml:5:12</span></summary>\n\
+            <div class=\"tile fail\"><span class=\"word\">FAIL</span> [0] zoo &gt; birds &gt; parrot \
+            <span class=\"location\">#a1b2 3.0ms</span><pre>The &lt;parrot&gt; flew away</pre>\
+            <details><summary>data</summary><table><tr><td>animal</td><td>parrot</td></tr></table>\
+            </details></div>\n\
+            </details>\n\
+            <details><summary class=\"pending\">dodo (pending: extinct) \
+            <span class=\"location\">zoo.yaml:7:12</span></summary>\n\
+            </details>\n\
+            </details>\n\
+            <details><summary>horse <span class=\"location\">zoo.yaml:10:8</span></summary>\n\
+            <div class=\"tile pass\"><span class=\"word\">PASS</span> [0]  \
+            <span class=\"location\"># 0.0ms</span>\
+            <details><summary>data</summary><table></table></details></div>\n\
+            </details>\n\
+            <details><summary class=\"unselected\">fish (not selected) \
+            <span class=\"location\">zoo.yaml:12:8</span></summary>\n\
+            </details>\n\
+            </details>\n\
+            </details>\n\
+            </body>\n</html>\n"
+        );
+    }
+
+    #[test]
+    fn it_escapes_the_html_special_characters() {
+        assert_eq!(
+            escape("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+}
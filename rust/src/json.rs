@@ -0,0 +1,34 @@
+//! The JSON files and outputs: the baseline, the state file and the JSON
+//! list. They need the `json` feature, which brings serde_json into the
+//! dependency graph of the crates using specimen. Without it, reading or
+//! writing JSON fails with an error, which the run reports as a diagnostic.
+
+use std::io;
+
+#[cfg(feature = "json")]
+pub fn from_str<T: serde::de::DeserializeOwned>(text: &str) -> io::Result<T> {
+    Ok(serde_json::from_str(text)?)
+}
+
+#[cfg(feature = "json")]
+pub fn to_string_pretty<T: serde::Serialize>(value: &T) -> io::Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(not(feature = "json"))]
+pub fn from_str<T>(_text: &str) -> io::Result<T> {
+    Err(disabled_error())
+}
+
+#[cfg(not(feature = "json"))]
+pub fn to_string_pretty<T>(_value: &T) -> io::Result<String> {
+    Err(disabled_error())
+}
+
+#[cfg(not(feature = "json"))]
+fn disabled_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "The json feature of specimen is disabled",
+    )
+}
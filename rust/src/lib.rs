@@ -1,47 +1,126 @@
+mod annotation;
+mod baseline;
+mod breakdown;
+pub mod condition;
+pub mod diagnostic;
+mod diff;
+pub mod documentation;
+mod explain;
 pub mod file;
 mod flag;
+mod html;
+mod json;
+mod list;
 mod nodule;
+pub mod options;
+mod report;
+mod rerun;
+mod sample;
+pub mod tag;
+mod terminal;
+mod timing;
 mod tree;
 
+pub use diff::expect_equal;
 use specimen__focustree as focustree;
 pub use specimen__multistringmap::Dict;
-use specimen__multistringmap::MultiStringMap;
 pub use specimen__writable::Writable;
-use specimen__yaml as yaml;
 
+use diagnostic::Diagnostic;
+use options::ListFormat;
+use options::Options;
+use options::Reporter;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
 use std::io;
 use std::io::Write;
+use std::path::Path;
 use std::rc::Rc;
-use std::time::Duration;
-use std::time::SystemTime;
+use std::time::Instant;
 
-#[derive(Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum FailStatus {
     #[default]
     Pristine,
     Failed,
     Aborted,
     Panicked,
+    /// The tile failed, as expected by the XFAIL flag
+    ExpectedFailure,
+    /// The tile passed although the XFAIL flag expects it to fail
+    UnexpectedPass,
+}
+
+impl FailStatus {
+    /// returns true if the tile failed without being expected to.
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            FailStatus::Failed | FailStatus::Aborted | FailStatus::Panicked
+        )
+    }
+}
+
+thread_local! {
+    static TILE_NAME: RefCell<Box<str>> = RefCell::new("".into());
+}
+
+/// returns the display name of the tile being run, so that it can be used
+/// by the test box. See `tile_name` in the reports.
+pub fn tile_name() -> Box<str> {
+    TILE_NAME.with(|name| name.borrow().clone())
 }
 
 #[derive(Default)]
 struct S {
-    tile_count: u32,
-    tile_passed: u32,
-    tile_failed: u32,
-    tile_aborted: u32,
-    tile_panicked: u32,
-    failure_report: Vec<Box<str>>,
-
     status: FailStatus,
     fail_info: Box<str>,
 }
 
+/// A tile of a selected slab, listed before the run starts
+struct PlannedTile<'n, 'a> {
+    slab: &'n nodule::Nodule<'a>,
+    /// The index of the tile among the tiles of its slab
+    index: usize,
+    tile: Dict,
+    name: Box<str>,
+    id: Box<str>,
+}
+
+impl PlannedTile<'_, '_> {
+    /// returns true if the filter matches the name, the ID or the file path
+    /// of the tile, or one of its `key=value` pairs
+    fn matches(&self, filter: &Regex) -> bool {
+        filter.is_match(&self.name)
+            || filter.is_match(&self.id)
+            || filter.is_match(&self.slab.file_path)
+            || self
+                .tile
+                .iter()
+                .any(|(key, value)| filter.is_match(&format!("{key}={value}")))
+    }
+}
+
 pub fn run(
     test_box: &mut dyn FnMut(&Dict) -> Result<(), Box<str>>,
     file_slice: &[file::File],
 ) -> bool {
-    let result = ioless_run(test_box, file_slice, &mut Writable::Out(io::stdout()));
+    run_with_options(test_box, file_slice, &Options::from_env())
+}
+
+pub fn run_with_options(
+    test_box: &mut dyn FnMut(&Dict) -> Result<(), Box<str>>,
+    file_slice: &[file::File],
+    options: &Options,
+) -> bool {
+    let result = ioless_run_with_options(
+        test_box,
+        file_slice,
+        options,
+        &mut Writable::Out(io::stdout()),
+    );
     match result {
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -56,145 +135,529 @@ pub fn ioless_run(
     file_slice: &[file::File],
     stdout: &mut Writable,
 ) -> io::Result<bool> {
-    // Parse the data into a Root, which contains Nodule-s
+    ioless_run_with_options(test_box, file_slice, &Options::default(), stdout)
+}
 
-    let mut document_store = Vec::from_iter(file_slice.iter().map(|_| Box::new(Vec::new())));
+pub fn ioless_run_with_options(
+    test_box: &mut dyn FnMut(&Dict) -> Result<(), Box<str>>,
+    file_slice: &[file::File],
+    options: &Options,
+    stdout: &mut Writable,
+) -> io::Result<bool> {
+    ioless_run_with_result(test_box, file_slice, options, stdout).map(|result| result.success)
+}
+
+/// The outcome of a run, as returned by `ioless_run_with_result`
+#[derive(Debug)]
+pub struct RunResult {
+    /// true if all the tiles passed and no error was issued
+    pub success: bool,
+    /// The warnings and errors issued while parsing the files and selecting
+    /// the slabs, in the order they were issued
+    pub diagnostic_vec: Vec<Diagnostic>,
+}
 
-    let mut root_nodule_vec: Vec<nodule::Nodule> = file_slice
+pub fn ioless_run_with_result(
+    test_box: &mut dyn FnMut(&Dict) -> Result<(), Box<str>>,
+    file_slice: &[file::File],
+    options: &Options,
+    stdout: &mut Writable,
+) -> io::Result<RunResult> {
+    // The diagnostics are gathered, so that they can be printed by the
+    // selected reporter. Those of the options come first.
+    let mut diagnostic_vec = options.diagnostic_vec.clone();
+    if options.list == Some(ListFormat::Json) && !cfg!(feature = "json") {
+        diagnostic_vec.push(Diagnostic::option_error(
+            "SPECIMEN_LIST",
+            "The JSON list needs the json feature of specimen",
+        ));
+    }
+    let has_option_error = diagnostic_vec
         .iter()
-        .zip(document_store.iter_mut())
-        .map(|(f, s)| nodule::Nodule::parse_file(f, s))
-        .flatten()
-        .collect();
-
-    for nodule in root_nodule_vec.iter_mut() {
-        let mut data_matrix = MultiStringMap::new();
-        data_matrix.0.insert(
-            Box::from("filepath"),
-            Rc::new([Box::from((*nodule.file_path).to_owned())]),
-        );
-
-        if let Err(e) = nodule.populate(&data_matrix) {
-            panic!(
-                "Failed to populate nodule data matrix for file {} because: {}",
-                nodule.file_path, e
-            );
-        }
+        .any(|diagnostic| diagnostic.severity == diagnostic::Severity::Error);
+
+    // Parse the data into a Root, which contains Nodule-s
+
+    let mut document_store = Vec::from_iter(file_slice.iter().map(|_| Vec::new()));
+
+    let mut root = nodule::Nodule::parse_root(file_slice, &mut document_store, &mut diagnostic_vec);
+
+    // The strict mode forbids the FOCUS flags of the spec files, but not the
+    // focus locations of the options
+    let mut focus_error_vec = Vec::new();
+    if options.strict {
+        root.push_focus_errors(&mut focus_error_vec);
     }
 
-    let root = nodule::Nodule {
-        node: &yaml::BAD_VALUE,
-        flag: focustree::Flag::None,
-        is_leaf: false,
-        file_path: Rc::from("".to_owned()),
-        data_matrix: MultiStringMap::new(),
-        children: root_nodule_vec.into_boxed_slice(),
-    };
+    // The focus locations given in the options act as FOCUS flags
+    for location in options.focus_location_vec.iter() {
+        if let Err(message) = root.focus_at(location) {
+            diagnostic_vec.push(Diagnostic::warning(
+                &location.path.to_string_lossy(),
+                specimen__yaml::Position {
+                    line: location.line,
+                    column: 0,
+                },
+                &message,
+            ));
+        }
+    }
 
     // Retrieving focused nodes, if any. This is done using a suffix tree-traversal: The presence of the FOCUS flag on a node is checked after all its children havec been checked. If a node which has FOCUS-ed children is FOCUS-ed itself, then its FOCUS flag is ignored and a warning is issued.
     let mut selected_leaves = Vec::new();
     let mut flag_stat = focustree::FlagStat::default();
-    focustree::extract_focused_leaf_values(&root, &mut selected_leaves, &mut flag_stat, stdout);
+    focustree::extract_focused_leaf_values(&root, &mut selected_leaves, &mut flag_stat);
+    for warning in flag_stat.warning_vec.iter() {
+        diagnostic_vec.push(Diagnostic::warning(
+            &warning.value.file_path,
+            warning.value.node.position,
+            warning.message,
+        ));
+    }
+    if options.strict {
+        for diagnostic in diagnostic_vec.iter_mut() {
+            diagnostic.severity = diagnostic::Severity::Error;
+        }
+        diagnostic_vec.append(&mut focus_error_vec);
+    }
+
+    let mut report = report::Report {
+        diagnostic_vec,
+        focus_count: flag_stat.focus_count,
+        skip_count: flag_stat.skip_count,
+        focus_location_vec: flag_stat
+            .focus_value_vec
+            .iter()
+            .map(|nodule| nodule.get_location())
+            .collect(),
+        focused_value_count: flag_stat.focused_value_count,
+        pending_value_count: flag_stat.skipped_value_count,
+        strict: options.strict,
+        ..report::Report::default()
+    };
+    let today = flag::Date::today();
+    for nodule in flag_stat.skip_value_vec.iter() {
+        report.skip_vec.push(report::SkipReport {
+            file_path: Rc::clone(&nodule.file_path),
+            location: nodule.get_location(),
+            box_name: nodule
+                .data_matrix
+                .0
+                .get("box")
+                .map(|value| value[0].clone()),
+            reason: nodule.flag_reason.clone(),
+            until: nodule.flag_until,
+            expired: nodule.flag_until.is_some_and(|until| until < today),
+        });
+    }
 
-    let start_time = SystemTime::now();
+    // The JSON list includes the diagnostics
+    match options.reporter {
+        #[cfg(feature = "json")]
+        _ if options.list == Some(ListFormat::Json) => {}
+        Reporter::Text => report::write_diagnostics(&report.diagnostic_vec, stdout)?,
+        Reporter::Annotations => annotation::write_diagnostics(&report.diagnostic_vec, stdout)?,
+    }
 
-    // Run all the selected leaves
-    let mut s = S::default();
-    for slab in selected_leaves.into_iter() {
-        let slab_location = slab.get_location();
+    // An invalid option stops the run, since ignoring it would run other
+    // tiles, e.g. the whole suite in each job of a sharded run
+    if has_option_error {
+        match options.list {
+            #[cfg(feature = "json")]
+            Some(ListFormat::Json) => list::write_json(&[], &report, stdout)?,
+            _ => writeln!(
+                stdout,
+                "FAILURE -- No tile has been run because of the errors in the options"
+            )?,
+        }
+        return Ok(RunResult {
+            success: false,
+            diagnostic_vec: report.diagnostic_vec,
+        });
+    }
 
+    // List the tiles of the selected slabs before running them, so that they
+    // can be filtered. The tiles of the slabs whose conditions do not hold
+    // are skipped.
+    let mut identifier = report::TileIdentifier::default();
+    let mut planned_vec = Vec::new();
+    let mut id_vec: Vec<Box<str>> = Vec::new();
+    report.seed = match (options.seed, options.shard) {
+        (Some(seed), _) => seed,
+        (None, Some(_)) => sample::SHARD_SEED,
+        (None, None) => sample::random_seed(),
+    };
+    for slab in selected_leaves.iter() {
+        let unmet = condition::check_all(&slab.condition_vec, &options.predicate_map).err();
+        // Only a random sample of the tiles of the large slabs is run
+        let total_count = slab.data_matrix.product_len();
+        let sampled_index_vec = match slab.sample.or(options.sample_count) {
+            Some(sample_count) if sample_count < total_count && unmet.is_none() => {
+                let slab_key = format!("{}\n{}", slab.file_path, slab.tree_path.join("\n"));
+                Some(sample::sample_indices(
+                    sample_count,
+                    total_count,
+                    sample::slab_seed(report.seed, &slab_key),
+                ))
+            }
+            _ => None,
+        };
         let mut index = 0;
-        let mut iterator = slab.data_matrix.into_product_iterator();
+        let mut iterator = slab.data_matrix.clone().into_product_iterator();
         while let Some(tile) = iterator.next() {
-            // Pass the slab data to the testbox
-            // - Manage the context (s, test start and test end)
-            // - Recover from any panic that might arise during the testbox call
-
-            // Tile Start
-            s.status = FailStatus::Pristine;
-            s.fail_info = "".into();
-
-            // Tile Run
-            match test_box(&tile) {
-                Ok(()) => {}
-                Err(message) => {
-                    if message.starts_with("ABORT") {
-                        s.status = FailStatus::Aborted;
-                        s.fail_info = message["ABORT".len()..].into();
-                    } else {
-                        s.status = FailStatus::Failed;
-                        s.fail_info = message;
-                    }
-                }
+            let id = identifier.identify(&slab.get_tile_key(tile));
+            id_vec.push(id.clone());
+            let is_sampled = sampled_index_vec
+                .as_ref()
+                .is_none_or(|index_vec| index_vec.binary_search(&index).is_ok());
+            if unmet.is_none() && is_sampled {
+                planned_vec.push(PlannedTile {
+                    slab,
+                    index,
+                    tile: tile.clone(),
+                    id,
+                    name: slab.get_tile_name(tile),
+                });
             }
+            index += 1;
+        }
+        if let Some(reason) = unmet {
+            report.condition_skip_vec.push(report::ConditionSkipReport {
+                location: slab.get_location(),
+                reason: reason.into(),
+                tile_count: index,
+            });
+        }
+        if let Some(index_vec) = sampled_index_vec {
+            report.sample_vec.push(report::SampleReport {
+                location: slab.get_location(),
+                total_count,
+                index_vec,
+            });
+        }
+    }
 
-            // Tile End
-            s.tile_count += 1;
-            match s.status {
-                FailStatus::Pristine => {
-                    s.tile_passed += 1;
-                }
-                FailStatus::Failed => {
-                    s.tile_failed += 1;
-                }
-                FailStatus::Aborted => {
-                    s.tile_aborted += 1;
-                }
-                FailStatus::Panicked => {
-                    s.tile_panicked += 1;
+    if options.explain {
+        explain::write_explanation(&root, &planned_vec, &report, options, stdout)?;
+        return Ok(RunResult {
+            success: report.is_success(),
+            diagnostic_vec: report.diagnostic_vec,
+        });
+    }
+
+    if let Some(tag_expression) = &options.tag_expression {
+        let planned_count = planned_vec.len();
+        planned_vec.retain(|planned| tag_expression.matches(&planned.slab.tag_vec));
+        report.tag_skip_count = planned_count - planned_vec.len();
+    }
+
+    if let Some(filter) = &options.filter {
+        let planned_count = planned_vec.len();
+        planned_vec.retain(|planned| planned.matches(filter));
+        report.filtered_count = planned_count - planned_vec.len();
+    }
+
+    if let Some(format) = options.list {
+        match format {
+            ListFormat::Text => list::write_text(&planned_vec, stdout)?,
+            #[cfg(feature = "json")]
+            ListFormat::Json => list::write_json(&planned_vec, &report, stdout)?,
+            #[cfg(not(feature = "json"))]
+            ListFormat::Json => {
+                unreachable!("The JSON list stops the run without the json feature")
+            }
+        }
+        return Ok(RunResult {
+            success: report.is_success(),
+            diagnostic_vec: report.diagnostic_vec,
+        });
+    }
+
+    if let Some(shard) = &options.shard {
+        let planned_count = planned_vec.len();
+        planned_vec = planned_vec
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| shard.contains(*position))
+            .map(|(_, planned)| planned)
+            .collect();
+        writeln!(
+            stdout,
+            "Running the shard {}/{}: {} of {} tile(s)",
+            shard.index,
+            shard.count,
+            planned_vec.len(),
+            planned_count
+        )?;
+    }
+
+    let previous_failed_set = match &options.state_path {
+        Some(state_path) if state_path.exists() => match rerun::read_failed(state_path) {
+            Ok(failed_set) => failed_set,
+            Err(e) => {
+                let diagnostic = file_diagnostic(
+                    state_path,
+                    &format!("The state file could not be read: {e}"),
+                    options,
+                );
+                push_diagnostic(&mut report, diagnostic, options, stdout)?;
+                HashSet::new()
+            }
+        },
+        _ => HashSet::new(),
+    };
+    if options.last_failed {
+        let failed_count = planned_vec
+            .iter()
+            .filter(|planned| previous_failed_set.contains(&planned.id))
+            .count();
+        let has_state = options
+            .state_path
+            .as_ref()
+            .is_some_and(|state_path| state_path.exists());
+        if failed_count > 0 {
+            planned_vec.retain(|planned| previous_failed_set.contains(&planned.id));
+            writeln!(
+                stdout,
+                "Running only the {failed_count} tile(s) which failed in the last run"
+            )?;
+        } else if !has_state {
+            // The state is only recorded on request, so the last run may
+            // have failed without recording anything
+            writeln!(
+                stdout,
+                "No state file was found, all the tiles are run. The failed tiles are only \
+                recorded by the runs with SPECIMEN_STATE or SPECIMEN_LAST_FAILED set, \
+                e.g. SPECIMEN_LAST_FAILED=0"
+            )?;
+        } else {
+            writeln!(
+                stdout,
+                "No failed tile was recorded by the last run, all the tiles are run"
+            )?;
+        }
+    }
+    if !options.id_vec.is_empty() {
+        planned_vec.retain(|planned| {
+            options
+                .id_vec
+                .iter()
+                .any(|requested_id| report::id_matches(&planned.id, requested_id))
+        });
+        writeln!(
+            stdout,
+            "Running only the {} tile(s) matching the requested ID(s)",
+            planned_vec.len()
+        )?;
+    }
+
+    let start_time = Instant::now();
+
+    let mut terminal = match options.reporter {
+        Reporter::Text => Some(terminal::Terminal::new(
+            options.verbose,
+            planned_vec.len(),
+            stdout,
+        )),
+        Reporter::Annotations => None,
+    };
+
+    // The tile name is restored at the end of the run, in case this run is
+    // nested in the test box of another run
+    let outer_tile_name = tile_name();
+
+    // Run all the planned tiles
+    let mut s = S::default();
+    for planned in planned_vec.into_iter() {
+        let slab = planned.slab;
+        let tile = &planned.tile;
+
+        // Pass the slab data to the testbox
+        // - Manage the context (s, test start and test end)
+        // - Recover from any panic that might arise during the testbox call
+
+        // Tile Start
+        s.status = FailStatus::Pristine;
+        s.fail_info = "".into();
+        TILE_NAME.with(|tile_name| *tile_name.borrow_mut() = planned.name.clone());
+
+        // Tile Run
+        let tile_start = Instant::now();
+        let result = test_box(tile);
+        let duration = tile_start.elapsed();
+        match result {
+            Ok(()) => {}
+            Err(message) => {
+                if let Some(info) = message.strip_prefix("ABORT") {
+                    s.status = FailStatus::Aborted;
+                    s.fail_info = info.into();
+                } else {
+                    s.status = FailStatus::Failed;
+                    s.fail_info = message;
                 }
             }
-            // summarize the failures
-            if s.status != FailStatus::Pristine {
-                let word = match s.status {
-                    FailStatus::Failed => "FAIL",
-                    FailStatus::Aborted => "ABORT",
-                    FailStatus::Panicked => "PANIC",
-                    _ => "",
-                };
-                let message = format!("{word}[{slab_location}][{index}]: {}", s.fail_info);
-
-                s.failure_report.push(Box::from(message));
+        }
+
+        if slab.expect_failure {
+            if s.status == FailStatus::Pristine {
+                s.status = FailStatus::UnexpectedPass;
+                s.fail_info = "The tile passed although it is expected to fail".into();
+            } else {
+                s.status = FailStatus::ExpectedFailure;
             }
+        }
 
-            index += 1;
+        // Tile End
+        report.tile_vec.push(report::TileReport {
+            file_path: Rc::clone(&slab.file_path),
+            slab_location: slab.get_location(),
+            index: planned.index,
+            name: planned.name,
+            id: planned.id,
+            data: planned.tile,
+            status: s.status,
+            message: std::mem::take(&mut s.fail_info),
+            duration,
+            slow_threshold: slab.slow.or(options.slow_threshold),
+        });
+        if let Some(terminal) = terminal.as_mut() {
+            terminal.tile_end(report.tile_vec.last().unwrap(), stdout)?;
         }
     }
 
-    let duration = SystemTime::now()
-        .duration_since(start_time)
-        .unwrap_or_else(|_| Duration::default());
+    if let Some(terminal) = terminal.as_mut() {
+        terminal.finish(stdout)?;
+    }
+    TILE_NAME.with(|tile_name| *tile_name.borrow_mut() = outer_tile_name);
+
+    report.duration = start_time.elapsed();
 
-    let outcome = if s.failure_report.len() == 0 {
-        "SUCCESS"
-    } else {
-        writeln!(stdout, "{}", s.failure_report.join("\n"))?;
-        "FAILURE"
+    // The files are read and written before the summary, so that their
+    // diagnostics count in the outcome of the run
+    if let Some(state_path) = &options.state_path {
+        if let Err(e) = rerun::write_failed(state_path, &previous_failed_set, &id_vec, &report) {
+            let diagnostic = file_diagnostic(
+                state_path,
+                &format!("The state file could not be written: {e}"),
+                options,
+            );
+            push_diagnostic(&mut report, diagnostic, options, stdout)?;
+        }
+    }
+
+    let previous_baseline = match &options.baseline_path {
+        Some(baseline_path) => match baseline::Baseline::read(baseline_path) {
+            Ok(previous) => Some(previous),
+            Err(e) => {
+                let diagnostic = file_diagnostic(
+                    baseline_path,
+                    &format!("The baseline could not be read: {e}"),
+                    options,
+                );
+                push_diagnostic(&mut report, diagnostic, options, stdout)?;
+                None
+            }
+        },
+        None => None,
     };
 
-    if flag_stat.focus_count > 0 || flag_stat.skip_count > 0 {
-        let mut message_vec = vec![];
-        if flag_stat.focus_count > 0 {
-            message_vec.push(format!("{} focused node(s)", flag_stat.focus_count));
+    let current_baseline = (previous_baseline.is_some() || options.save_baseline_path.is_some())
+        .then(|| baseline::Baseline::from_report(&report));
+    if let (Some(current), Some(save_baseline_path)) =
+        (&current_baseline, &options.save_baseline_path)
+    {
+        if let Err(e) = current.write(save_baseline_path) {
+            let diagnostic = file_diagnostic(
+                save_baseline_path,
+                &format!("The baseline could not be saved: {e}"),
+                options,
+            );
+            push_diagnostic(&mut report, diagnostic, options, stdout)?;
         }
-        if flag_stat.skip_count > 0 {
-            message_vec.push(format!("{} pending node(s)", flag_stat.skip_count));
+    }
+
+    if let Some(html_path) = &options.html_path {
+        if let Err(e) = html::write_file(html_path, &root, &report) {
+            let diagnostic = file_diagnostic(
+                html_path,
+                &format!("The HTML report could not be written: {e}"),
+                options,
+            );
+            push_diagnostic(&mut report, diagnostic, options, stdout)?;
+        }
+    }
+    if let Some(documentation_path) = &options.documentation_path {
+        let format = documentation::DocFormat::from_path(documentation_path);
+        let text = documentation::render(&root, format, Some(&report));
+        if let Err(e) = fs::write(documentation_path, text) {
+            let diagnostic = file_diagnostic(
+                documentation_path,
+                &format!("The documentation could not be written: {e}"),
+                options,
+            );
+            push_diagnostic(&mut report, diagnostic, options, stdout)?;
         }
-        writeln!(stdout, "Encountered {}", message_vec.join(" and "))?;
     }
 
-    writeln!(
-        stdout,
-        "Ran {} tiles in {}ms\n\
-        {} -- {} Passed | {} Failed | {} Aborted | {} Panicked",
-        s.tile_count,
-        duration.as_millis(),
-        outcome,
-        s.tile_passed,
-        s.tile_failed,
-        s.tile_aborted,
-        s.tile_panicked,
-    )?;
+    match options.reporter {
+        Reporter::Text => report::write_failures(&report, stdout)?,
+        Reporter::Annotations => annotation::write_failures(&report, stdout)?,
+    }
+    match options.reporter {
+        Reporter::Text if options.verbose => report::write_flag_locations(&report, stdout)?,
+        Reporter::Text => report::write_pending(&report, stdout)?,
+        Reporter::Annotations => annotation::write_pending(&report, stdout)?,
+    }
+    report::write_summary(&report, stdout)?;
+    if options.reporter == Reporter::Text {
+        report::write_failed_tiles(&report, stdout)?;
+    }
+    breakdown::write_breakdown(&report, stdout)?;
+    timing::write_slowest(&report, options.slowest_count, stdout)?;
+    match options.reporter {
+        Reporter::Text => timing::write_slow(&report, stdout)?,
+        Reporter::Annotations => annotation::write_slow(&report, stdout)?,
+    }
+
+    if let (Some(previous), Some(current), Some(baseline_path)) = (
+        &previous_baseline,
+        &current_baseline,
+        &options.baseline_path,
+    ) {
+        baseline::Comparison::new(previous, current).write(baseline_path, stdout)?;
+    }
 
-    Ok(outcome == "SUCCESS")
+    Ok(RunResult {
+        success: report.is_success(),
+        diagnostic_vec: report.diagnostic_vec,
+    })
+}
+
+/// returns the diagnostic about a file which is not a spec file, such as the
+/// state file. It is an error in strict mode, like the other diagnostics.
+fn file_diagnostic(path: &Path, message: &str, options: &Options) -> Diagnostic {
+    let path = path.to_string_lossy();
+    let position = specimen__yaml::Position { line: 1, column: 0 };
+    if options.strict {
+        Diagnostic::error(&path, position, message)
+    } else {
+        Diagnostic::warning(&path, position, message)
+    }
+}
+
+/// Writes a diagnostic issued after the diagnostics of the spec files have
+/// been written, with the selected reporter, and adds it to the report.
+fn push_diagnostic(
+    report: &mut report::Report,
+    diagnostic: Diagnostic,
+    options: &Options,
+    stdout: &mut Writable,
+) -> io::Result<()> {
+    let diagnostic_slice = std::slice::from_ref(&diagnostic);
+    match options.reporter {
+        Reporter::Text => report::write_diagnostics(diagnostic_slice, stdout)?,
+        Reporter::Annotations => annotation::write_diagnostics(diagnostic_slice, stdout)?,
+    }
+    report.diagnostic_vec.push(diagnostic);
+    Ok(())
 }
@@ -0,0 +1,135 @@
+//! The list mode runs no box. It lists the tiles selected by the flags, the
+//! conditions, the tags and the filter, with their ID, location, name and
+//! data, for the tools which discover the tiles and for the reviews of the
+//! spec files.
+
+#[cfg(feature = "json")]
+use crate::diagnostic::Severity;
+#[cfg(feature = "json")]
+use crate::json;
+#[cfg(feature = "json")]
+use crate::report::Report;
+use crate::PlannedTile;
+#[cfg(feature = "json")]
+use serde::Serialize;
+use specimen__writable::Writable;
+#[cfg(feature = "json")]
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write;
+
+/// The JSON output of the list mode
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct Listing<'r> {
+    tiles: Vec<ListedTile<'r>>,
+    focused: Vec<&'r str>,
+    pending: Vec<ListedPending<'r>>,
+    diagnostics: Vec<ListedDiagnostic<'r>>,
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct ListedTile<'r> {
+    id: &'r str,
+    location: Box<str>,
+    index: usize,
+    name: &'r str,
+    data: BTreeMap<&'r str, &'r str>,
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct ListedPending<'r> {
+    location: &'r str,
+    reason: Option<&'r str>,
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct ListedDiagnostic<'r> {
+    severity: &'static str,
+    location: String,
+    message: &'r str,
+}
+
+/// returns the data of the tile, sorted by key
+fn sorted_data<'t>(planned: &'t PlannedTile) -> Vec<(&'t str, &'t str)> {
+    let mut entry_vec: Vec<(&str, &str)> = planned
+        .tile
+        .iter()
+        .map(|(key, value)| (&**key, &**value))
+        .collect();
+    entry_vec.sort();
+    entry_vec
+}
+
+/// Writes a line per tile, e.g.
+/// `[zoo.yaml:3:8][0] #b681b2fe3a54 zoo > horse (animal=horse, ...)`
+pub fn write_text(planned_slice: &[PlannedTile], stdout: &mut Writable) -> io::Result<()> {
+    for planned in planned_slice.iter() {
+        let data_vec: Vec<String> = sorted_data(planned)
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        writeln!(
+            stdout,
+            "[{}][{}] #{} {} ({})",
+            planned.slab.get_location(),
+            planned.index,
+            planned.id,
+            planned.name,
+            data_vec.join(", ")
+        )?;
+    }
+    writeln!(stdout, "{} tile(s)", planned_slice.len())
+}
+
+/// Writes the tiles as a JSON object, along with the locations of the
+/// focused and pending nodes, and the diagnostics, which are not printed
+/// separately so that the output stays valid JSON.
+#[cfg(feature = "json")]
+pub fn write_json(
+    planned_slice: &[PlannedTile],
+    report: &Report,
+    stdout: &mut Writable,
+) -> io::Result<()> {
+    let listing = Listing {
+        tiles: planned_slice
+            .iter()
+            .map(|planned| ListedTile {
+                id: &planned.id,
+                location: planned.slab.get_location(),
+                index: planned.index,
+                name: &planned.name,
+                data: sorted_data(planned).into_iter().collect(),
+            })
+            .collect(),
+        focused: report
+            .focus_location_vec
+            .iter()
+            .map(|location| &**location)
+            .collect(),
+        pending: report
+            .skip_vec
+            .iter()
+            .map(|skip| ListedPending {
+                location: &skip.location,
+                reason: skip.reason.as_deref(),
+            })
+            .collect(),
+        diagnostics: report
+            .diagnostic_vec
+            .iter()
+            .map(|diagnostic| ListedDiagnostic {
+                severity: match diagnostic.severity {
+                    Severity::Warning => "warning",
+                    Severity::Error => "error",
+                },
+                location: diagnostic.location(),
+                message: &diagnostic.message,
+            })
+            .collect(),
+    };
+    writeln!(stdout, "{}", json::to_string_pretty(&listing)?)
+}
@@ -1,29 +1,118 @@
+use crate::condition;
+use crate::diagnostic::Diagnostic;
 use crate::file;
 use crate::flag;
+use crate::options;
 use specimen__focustree as focustree;
+use specimen__multistringmap::Dict;
+use specimen__multistringmap::Mark;
 use specimen__multistringmap::MultiStringMap;
 use specimen__yaml as yaml;
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct Nodule<'a> {
     pub node: &'a yaml::Yaml,
     pub flag: focustree::Flag,
+    /// The `reason` attribute of the flag, if any
+    pub flag_reason: Option<Box<str>>,
+    /// The `until` attribute of the flag, if any
+    pub flag_until: Option<flag::Date>,
+    /// true if the nodule or one of its ancestors has the XFAIL flag
+    pub expect_failure: bool,
     pub is_leaf: bool,
     pub file_path: Rc<str>,
+    /// The `name` of the nodule, preceded by the `name`s of its ancestors
+    pub name_vec: Vec<Box<str>>,
+    /// The path of the nodule in the tree of its file: for the nodule and
+    /// each of its ancestors, its `name`, or its position among its siblings
+    /// (e.g. `#2`) if it has no name
+    pub tree_path: Vec<Box<str>>,
+    /// The duration above which the tiles are reported as slow, set with the
+    /// `slow` key and inherited by the descendants
+    pub slow: Option<Duration>,
+    /// The number of tiles of the slab to run, drawn at random from its
+    /// product, set with the `sample` key and inherited by the descendants
+    pub sample: Option<usize>,
+    /// The tags of the nodule, set with the `tags` key, along with the tags
+    /// of its ancestors
+    pub tag_vec: Vec<Box<str>>,
+    /// The conditions of the nodule, set with the `when` key, along with the
+    /// conditions of its ancestors
+    pub condition_vec: Vec<condition::Condition>,
     pub data_matrix: MultiStringMap,
     pub children: Box<[Nodule<'a>]>,
 }
 
 impl<'a> Nodule<'a> {
     // Associated functions
-    pub fn parse_file(file: &file::File, store: &'a mut Box<Vec<yaml::Yaml>>) -> Vec<Nodule<'a>> {
+
+    /// Parses the files into a root nodule whose children are the documents
+    /// of the files, and populates their data matrices. The store must hold
+    /// one entry per file; it keeps the YAML nodes the nodules refer to.
+    pub fn parse_root(
+        file_slice: &[file::File],
+        document_store: &'a mut [Vec<yaml::Yaml>],
+        diagnostic_vec: &mut Vec<Diagnostic>,
+    ) -> Nodule<'a> {
+        let mut root_nodule_vec: Vec<Nodule> = Vec::new();
+        for (f, s) in file_slice.iter().zip(document_store.iter_mut()) {
+            root_nodule_vec.extend(Nodule::parse_file(f, s, diagnostic_vec));
+        }
+
+        for nodule in root_nodule_vec.iter_mut() {
+            let mut data_matrix = MultiStringMap::new();
+            data_matrix.0.insert(
+                Box::from("filepath"),
+                Rc::new([Box::from((*nodule.file_path).to_owned())]),
+            );
+
+            if let Err(e) = nodule.populate(&data_matrix) {
+                panic!(
+                    "Failed to populate nodule data matrix for file {} because: {}",
+                    nodule.file_path, e
+                );
+            }
+        }
+
+        Nodule {
+            node: &yaml::BAD_VALUE,
+            flag: focustree::Flag::None,
+            flag_reason: None,
+            flag_until: None,
+            expect_failure: false,
+            is_leaf: false,
+            file_path: Rc::from("".to_owned()),
+            name_vec: Vec::new(),
+            tree_path: Vec::new(),
+            slow: None,
+            sample: None,
+            tag_vec: Vec::new(),
+            condition_vec: Vec::new(),
+            data_matrix: MultiStringMap::new(),
+            children: root_nodule_vec.into_boxed_slice(),
+        }
+    }
+
+    pub fn parse_file(
+        file: &file::File,
+        store: &'a mut Vec<yaml::Yaml>,
+        diagnostic_vec: &mut Vec<Diagnostic>,
+    ) -> Vec<Nodule<'a>> {
         let file_path = Rc::from(file.path.to_owned());
 
         let mut document_vec = match yaml::YamlLoader::load_from_str(&file.content) {
             Ok(v) => v,
             Err(e) => {
-                println!("{}: {}", file.path, e);
+                let marker = e.marker();
+                let position = yaml::Position {
+                    line: marker.line(),
+                    column: marker.col(),
+                };
+                diagnostic_vec.push(Diagnostic::warning(&file.path, position, &e.to_string()));
                 vec![]
             }
         };
@@ -32,7 +121,8 @@ impl<'a> Nodule<'a> {
 
         store
             .iter()
-            .map(|node| {
+            .enumerate()
+            .map(|(position, node)| {
                 match node.data {
                     yaml::YamlData::Mapping(_) => {}
                     _ => panic!("The root node of the YAML test data file must be a mapping."),
@@ -45,15 +135,24 @@ impl<'a> Nodule<'a> {
                 );
 
                 let mut n = Nodule {
-                    node: &node,
+                    node,
                     flag: focustree::Flag::None,
+                    flag_reason: None,
+                    flag_until: None,
+                    expect_failure: false,
                     is_leaf: true,
                     file_path: Rc::clone(&file_path),
+                    name_vec: Vec::new(),
+                    tree_path: vec![path_segment(node, position)],
+                    slow: None,
+                    sample: None,
+                    tag_vec: Vec::new(),
+                    condition_vec: Vec::new(),
                     data_matrix,
                     children: Box::new([]),
                 };
 
-                n.initalize_tree();
+                n.initalize_tree(diagnostic_vec);
 
                 n
             })
@@ -69,16 +168,264 @@ impl<'a> Nodule<'a> {
         .into()
     }
 
+    /// Marks as focused the nodule of the given location, like a FOCUS flag.
+    /// It is the innermost nodule containing the line of the location, see
+    /// `innermost_at`. returns an error message if there is no such nodule,
+    /// if the path of the location matches several spec files or if the
+    /// nodule is pending.
+    pub fn focus_at(&mut self, location: &options::FocusLocation) -> Result<(), String> {
+        let file_path = self.find_file_path(&location.path)?;
+        let document = self
+            .children
+            .iter_mut()
+            .filter(|document| document.file_path == file_path)
+            .rfind(|document| document.node.position.line <= location.line)
+            .ok_or_else(|| "No nodule was found at this location".to_owned())?;
+        let nodule = document.innermost_at(location.line);
+        if nodule.flag == focustree::Flag::Skip {
+            return Err(format!(
+                "The nodule at {} is pending, it cannot be focused",
+                nodule.get_location()
+            ));
+        }
+        nodule.flag = focustree::Flag::Focus;
+        Ok(())
+    }
+
+    /// returns the path of the spec file designated by the path of a focus
+    /// location: the file with the same path, or else the only file whose
+    /// path ends with it, e.g. `spec/zoo.yaml` for `zoo.yaml`
+    fn find_file_path(&self, path: &Path) -> Result<Rc<str>, String> {
+        let mut file_path_vec: Vec<&Rc<str>> = self
+            .children
+            .iter()
+            .map(|document| &document.file_path)
+            .collect();
+        // The documents of a file are adjacent
+        file_path_vec.dedup();
+        if let Some(file_path) = file_path_vec
+            .iter()
+            .find(|file_path| is_same_file(file_path, path))
+        {
+            return Ok(Rc::clone(file_path));
+        }
+        let suffix_match_vec: Vec<&Rc<str>> = file_path_vec
+            .into_iter()
+            .filter(|file_path| Path::new(&***file_path).ends_with(path))
+            .collect();
+        match suffix_match_vec[..] {
+            [] => Err("No nodule was found at this location".to_owned()),
+            [file_path] => Ok(Rc::clone(file_path)),
+            _ => Err(format!(
+                "The path matches several spec files: {}. It has been ignored.",
+                suffix_match_vec
+                    .iter()
+                    .map(|file_path| &***file_path)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Issues an error for each FOCUS flag and each `!FOCUS` tag of the tree,
+    /// which the strict mode forbids
+    pub fn push_focus_errors(&self, diagnostic_vec: &mut Vec<Diagnostic>) {
+        if self.flag == focustree::Flag::Focus {
+            diagnostic_vec.push(Diagnostic::error(
+                &self.file_path,
+                self.node.data["flag"].position,
+                "The FOCUS flag is not allowed in strict mode",
+            ));
+        }
+        for value_node in self.value_node_vec() {
+            if value_mark(value_node) == Mark::Focus {
+                diagnostic_vec.push(Diagnostic::error(
+                    &self.file_path,
+                    value_node.position,
+                    "The !FOCUS tag is not allowed in strict mode",
+                ));
+            }
+        }
+        for child in self.children.iter() {
+            child.push_focus_errors(diagnostic_vec);
+        }
+    }
+
+    /// returns the innermost nodule containing the line. A child spans from
+    /// its start to the start of the next child, or to its last line for the
+    /// last child; the other lines belong to the nodule itself.
+    fn innermost_at(&mut self, line: usize) -> &mut Nodule<'a> {
+        let child_count = self.children.len();
+        let position = (0..child_count).find(|k| {
+            let child = &self.children[*k];
+            let is_before_end = match self.children.get(k + 1) {
+                Some(next) => line < next.node.position.line,
+                None => line <= last_line(child.node),
+            };
+            child.node.position.line <= line && is_before_end
+        });
+        match position {
+            Some(k) => self.children[k].innermost_at(line),
+            None => self,
+        }
+    }
+
+    /// returns the marks set with the `!FOCUS` and `!PENDING` tags on the
+    /// values of the data keys of the nodule, e.g. `animal: [mouse, !FOCUS cat]`
+    pub fn get_value_mark_vec(&self) -> Vec<Mark> {
+        self.value_node_vec()
+            .into_iter()
+            .map(value_mark)
+            .filter(|mark| *mark != Mark::None)
+            .collect()
+    }
+
+    /// returns the value nodes of the data keys of the nodule, including the
+    /// entries of the sequences
+    fn value_node_vec(&self) -> Vec<&'a yaml::Yaml> {
+        let mut value_node_vec = Vec::new();
+        if let yaml::YamlData::Mapping(ref mapping) = self.node.data {
+            for (key, value) in mapping.iter() {
+                if let yaml::YamlData::String(ref key) = key.data {
+                    if is_reserved_key(key) {
+                        continue;
+                    }
+                }
+                match value.data {
+                    yaml::YamlData::List(ref list) => value_node_vec.extend(list.iter()),
+                    _ => value_node_vec.push(value),
+                }
+            }
+        }
+        value_node_vec
+    }
+
+    /// Builds the display name of a tile of the slab, from the names of the
+    /// nodule and its ancestors, followed by the values of the matrix keys
+    /// which take more than one value. E.g. `zoo > animal matrix [animal=cat]`
+    pub fn get_tile_name(&self, tile: &Dict) -> Box<str> {
+        let mut name = self.name_vec.join(" > ");
+        let varying_vec: Vec<String> = self
+            .data_matrix
+            .0
+            .iter()
+            .filter(|(_, value_array)| value_array.len() > 1)
+            .map(|(key, _)| format!("{}={}", key, tile[key]))
+            .collect();
+        if !varying_vec.is_empty() {
+            if !name.is_empty() {
+                name.push(' ');
+            }
+            name.push_str(&format!("[{}]", varying_vec.join(", ")));
+        }
+        name.into()
+    }
+
+    /// Builds the text a tile ID is derived from: the file path, the tree
+    /// path of the slab and the values of the matrix keys which take more
+    /// than one value. It does not depend on the line numbers.
+    pub fn get_tile_key(&self, tile: &Dict) -> String {
+        let mut varying_vec: Vec<String> = self
+            .data_matrix
+            .0
+            .iter()
+            .filter(|(_, value_array)| value_array.len() > 1)
+            .map(|(key, _)| format!("{}={}", key, tile[key]))
+            .collect();
+        varying_vec.sort();
+        format!(
+            "{}\n{}\n{}",
+            self.file_path,
+            self.tree_path.join("\n"),
+            varying_vec.join("\n")
+        )
+    }
+
     // The initialization creates all the nodules which correspond to the mapping nodes of the yaml tree, except for the PENDING nodes. It fills the fields `flag`, `has_content_key` and `children`. **It expects YamlNode and FilePath to be already set**, and it sets YamlNode and FilePath for its children.
-    fn initalize_tree(&mut self) {
+    fn initalize_tree(&mut self, diagnostic_vec: &mut Vec<Diagnostic>) {
         match self.node.data {
             yaml::YamlData::Mapping(_) => {}
             _ => panic!("the content descendant nodes must be yaml mappings"),
         }
 
+        if let yaml::YamlData::String(ref name) = self.node.data["name"].data {
+            self.name_vec.push(name.as_str().into());
+        }
+
+        let slow_node = &self.node.data["slow"];
+        if *slow_node != yaml::BAD_VALUE {
+            self.slow = match slow_node.data {
+                yaml::YamlData::String(ref text) => options::parse_duration(text),
+                yaml::YamlData::Integer(millis) => {
+                    u64::try_from(millis).ok().map(Duration::from_millis)
+                }
+                _ => None,
+            };
+            if self.slow.is_none() {
+                self.panic("the slow key must be a duration such as 500ms or 2s");
+            }
+        }
+
+        let sample_node = &self.node.data["sample"];
+        if *sample_node != yaml::BAD_VALUE {
+            self.sample = match sample_node.data {
+                yaml::YamlData::Integer(count) => usize::try_from(count).ok(),
+                yaml::YamlData::String(ref text) => text.trim().parse().ok(),
+                _ => None,
+            }
+            .filter(|count| *count > 0);
+            if self.sample.is_none() {
+                self.panic("the sample key must be a positive number of tiles");
+            }
+        }
+
+        let tags_node = &self.node.data["tags"];
+        if *tags_node != yaml::BAD_VALUE {
+            match tags_node.data {
+                yaml::YamlData::String(ref tag) => self.tag_vec.push(tag.as_str().into()),
+                yaml::YamlData::List(ref tag_list) => {
+                    for tag_node in tag_list.iter() {
+                        match tag_node.data {
+                            yaml::YamlData::String(ref tag) => {
+                                self.tag_vec.push(tag.as_str().into())
+                            }
+                            _ => {
+                                self.panic("the tags key must be a string or a sequence of strings")
+                            }
+                        }
+                    }
+                }
+                _ => self.panic("the tags key must be a string or a sequence of strings"),
+            }
+        }
+
+        let when_node = &self.node.data["when"];
+        if *when_node != yaml::BAD_VALUE {
+            match condition::Condition::parse(when_node) {
+                Ok(condition_vec) => self.condition_vec.extend(condition_vec),
+                Err(message) => self.panic(&message),
+            }
+        }
+
+        for value_node in self.value_node_vec() {
+            if let Some(tag) = &value_node.tag {
+                if value_mark(value_node) == Mark::None {
+                    diagnostic_vec.push(Diagnostic::warning(
+                        &self.file_path,
+                        value_node.position,
+                        &format!("Unrecognized tag \"!{tag}\". It has been ignored."),
+                    ));
+                }
+            }
+        }
+
         let flag_node = &self.node.data["flag"];
         if *flag_node != yaml::BAD_VALUE {
-            self.flag = flag::read_flag(&flag_node);
+            let flag_value = flag::read_flag(flag_node, &self.file_path, diagnostic_vec);
+            self.flag = flag_value.flag;
+            self.flag_reason = flag_value.reason;
+            self.flag_until = flag_value.until;
+            self.expect_failure |= flag_value.expect_failure;
         }
         if self.flag == focustree::Flag::Skip {
             return;
@@ -90,16 +437,28 @@ impl<'a> Nodule<'a> {
             if let yaml::YamlData::List(ref yaml_vec) = content_node.data {
                 self.children = yaml_vec
                     .iter()
-                    .map(|node| {
+                    .enumerate()
+                    .map(|(position, node)| {
+                        let mut tree_path = self.tree_path.clone();
+                        tree_path.push(path_segment(node, position));
                         let mut n = Nodule {
                             node,
                             flag: focustree::Flag::None,
+                            flag_reason: None,
+                            flag_until: None,
+                            expect_failure: self.expect_failure,
                             is_leaf: true,
                             file_path: Rc::clone(&self.file_path),
+                            name_vec: self.name_vec.clone(),
+                            tree_path,
+                            slow: self.slow,
+                            sample: self.sample,
+                            tag_vec: self.tag_vec.clone(),
+                            condition_vec: self.condition_vec.clone(),
                             children: Box::new([]),
                             data_matrix: MultiStringMap::new(),
                         };
-                        n.initalize_tree();
+                        n.initalize_tree(diagnostic_vec);
                         n
                     })
                     .collect();
@@ -127,7 +486,7 @@ impl<'a> Nodule<'a> {
                 yaml::YamlData::String(ref s) => s,
                 _ => self.panic("the keys of the mapping nodes must be strings"),
             };
-            if key == "flag" || key == "content" || key == "about" {
+            if is_reserved_key(key) {
                 continue;
             }
 
@@ -148,9 +507,15 @@ impl<'a> Nodule<'a> {
                 )),
             };
 
-            self.data_matrix
-                .0
-                .insert(key.to_owned().into_boxed_str(), Rc::from(value_vector));
+            let mark_vec: Vec<Mark> = match value.data {
+                yaml::YamlData::List(ref a) => a.iter().map(value_mark).collect(),
+                _ => vec![value_mark(value)],
+            };
+            self.data_matrix.insert_marked(
+                key.to_owned().into_boxed_str(),
+                Rc::from(value_vector),
+                &mark_vec,
+            );
         }
 
         for child in self.children.iter_mut() {
@@ -167,3 +532,60 @@ impl<'a> Nodule<'a> {
         )
     }
 }
+
+/// returns true if the key has a meaning for specimen, rather than being
+/// passed to the test box
+fn is_reserved_key(key: &str) -> bool {
+    matches!(
+        key,
+        "flag" | "content" | "about" | "slow" | "sample" | "tags" | "when"
+    )
+}
+
+/// returns the mark set by the tag of a value, e.g. `!FOCUS cat`
+fn value_mark(node: &yaml::Yaml) -> Mark {
+    match node.tag.as_deref() {
+        Some("FOCUS") => Mark::Focus,
+        Some("PENDING") => Mark::Skip,
+        _ => Mark::None,
+    }
+}
+
+/// returns the segment of the tree path of a nodule: its `name`, or its
+/// position among its siblings if it has no name
+fn path_segment(node: &yaml::Yaml, position: usize) -> Box<str> {
+    match node.data["name"].data {
+        yaml::YamlData::String(ref name) => name.as_str().into(),
+        _ => format!("#{position}").into(),
+    }
+}
+
+/// returns true if the path is the path of the spec file, as given or once
+/// canonicalized
+fn is_same_file(file_path: &str, path: &Path) -> bool {
+    let file_path = Path::new(file_path);
+    if file_path == path {
+        return true;
+    }
+    match (fs::canonicalize(file_path), fs::canonicalize(path)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// returns the last line of the node, that of its last descendant or of the
+/// end of its multi-line string
+fn last_line(node: &yaml::Yaml) -> usize {
+    match node.data {
+        yaml::YamlData::Mapping(ref mapping) => mapping
+            .iter()
+            .flat_map(|(key, value)| [last_line(key), last_line(value)])
+            .max(),
+        yaml::YamlData::List(ref list) => list.iter().map(last_line).max(),
+        yaml::YamlData::String(ref text) => {
+            Some(node.position.line + text.trim_end_matches('\n').matches('\n').count())
+        }
+        _ => None,
+    }
+    .map_or(node.position.line, |line| line.max(node.position.line))
+}
@@ -0,0 +1,434 @@
+use crate::condition::PredicateMap;
+use crate::diagnostic::Diagnostic;
+use crate::tag::TagExpression;
+use regex::Regex;
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The reporter used to print the outcome of a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Reporter {
+    /// The plain text report: FAIL lines followed by a summary.
+    #[default]
+    Text,
+    /// GitHub Actions workflow commands (`::error` and `::warning` lines),
+    /// which are displayed inline on the YAML files of a pull request.
+    Annotations,
+}
+
+/// The format of the list of the tiles, in list mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// A line per tile
+    Text,
+    /// A JSON object, for the tools which discover the tiles
+    Json,
+}
+
+/// A position in a spec file, given as `path:line`, e.g. `spec/flag.yaml:12`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusLocation {
+    pub path: PathBuf,
+    /// The line number, starting at 1
+    pub line: usize,
+}
+
+impl FocusLocation {
+    pub fn parse(text: &str) -> Option<FocusLocation> {
+        let (path, line) = text.trim().rsplit_once(':')?;
+        let line = line.parse().ok().filter(|line| *line > 0)?;
+        if path.is_empty() {
+            return None;
+        }
+        Some(FocusLocation {
+            path: PathBuf::from(path),
+            line,
+        })
+    }
+}
+
+/// A share of the selected tiles, given as `k/n`: the k-th of n shards,
+/// starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    pub fn parse(text: &str) -> Option<Shard> {
+        let (index, count) = text.trim().split_once('/')?;
+        let index = index.trim().parse().ok()?;
+        let count = count.trim().parse().ok()?;
+        if index == 0 || index > count {
+            return None;
+        }
+        Some(Shard { index, count })
+    }
+
+    /// returns true if the tile of the given position among the selected
+    /// tiles belongs to the shard. The tiles are dealt in turn to each
+    /// shard, so the shards differ by at most one tile.
+    pub fn contains(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+/// The options of a run. `Options::default()` gives the behavior of
+/// `ioless_run`, while `Options::from_env()` is what `run` uses.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub reporter: Reporter,
+    /// With the text reporter, print a line for each tile as soon as it has run
+    pub verbose: bool,
+    /// The number of slowest tiles listed after the summary
+    pub slowest_count: usize,
+    /// The duration above which a tile is reported as slow. It can be
+    /// overridden in the YAML files with the `slow` key.
+    pub slow_threshold: Option<Duration>,
+    /// The path of the HTML report to write, if any
+    pub html_path: Option<PathBuf>,
+    /// The path of the documentation to write, including the status of the
+    /// slabs. It is written in HTML if the path ends with `.html`, in
+    /// Markdown otherwise.
+    pub documentation_path: Option<PathBuf>,
+    /// The path of a baseline, saved by an earlier run, to compare the run to
+    pub baseline_path: Option<PathBuf>,
+    /// The path where the baseline of the run is saved
+    pub save_baseline_path: Option<PathBuf>,
+    /// The path of the state file, where the IDs of the failed tiles
+    /// are recorded. Nothing is recorded when it is not set.
+    pub state_path: Option<PathBuf>,
+    /// Run only the tiles which failed in the last run, as recorded in the
+    /// state file. All the tiles are run if no failure was recorded.
+    pub last_failed: bool,
+    /// Run only the tiles whose ID starts with one of these. The ID of a
+    /// tile is shown as `#id` in the reports.
+    pub id_vec: Vec<Box<str>>,
+    /// Run only the tiles whose name, ID, file path or one of whose
+    /// `key=value` pairs matches this regex. It applies to the tiles selected
+    /// by the FOCUS and PENDING flags.
+    pub filter: Option<Regex>,
+    /// The nodules to consider focused, as if they had a FOCUS flag: for each
+    /// location, the nodule which starts at its line, or else the innermost
+    /// nodule containing it
+    pub focus_location_vec: Vec<FocusLocation>,
+    /// Run only the tiles whose tags satisfy this expression, e.g.
+    /// `smoke & !slow`
+    pub tag_expression: Option<TagExpression>,
+    /// Run only a share of the selected tiles, to split a run across
+    /// several jobs
+    pub shard: Option<Shard>,
+    /// The predicates which the `predicate` conditions of the `when` key can
+    /// name, e.g. a check that a service is reachable
+    pub predicate_map: PredicateMap,
+    /// Make the warnings about the spec files errors, and fail the run if a
+    /// node is focused, so that a committed FOCUS cannot shrink the suite.
+    /// An unexpected pass of an XFAIL tile also fails the run.
+    pub strict: bool,
+    /// Run no box, and print each nodule with the reason why it is selected
+    /// or dropped, along with the tiles of the selected slabs
+    pub explain: bool,
+    /// Run no box, and list the tiles selected by the flags, the conditions,
+    /// the tags and the filter, with their ID, location, name and data
+    pub list: Option<ListFormat>,
+    /// The number of tiles to run, drawn at random, from each slab which has
+    /// more. It can be overridden in the YAML files with the `sample` key.
+    pub sample_count: Option<usize>,
+    /// The seed of the samples. A random seed is used if it is not set,
+    /// except for the sharded runs, whose shards must draw the same samples
+    /// and use the seed 0. It is printed in the report so that the run can
+    /// be replayed.
+    pub seed: Option<u64>,
+    /// The problems found while reading the options, reported at the start
+    /// of the run. An error stops the run before any box runs.
+    pub diagnostic_vec: Vec<Diagnostic>,
+}
+
+/// The state file used by `Options::from_env` when `SPECIMEN_LAST_FAILED`
+/// is set, relative to the current directory
+pub const DEFAULT_STATE_PATH: &str = ".specimen/last-failed.json";
+
+impl Options {
+    /// Reads the options from the `SPECIMEN_*` environment variables.
+    /// Unset variables keep their default value. The invalid values are
+    /// ignored with a warning, except those of `SPECIMEN_FILTER`,
+    /// `SPECIMEN_FOCUS`, `SPECIMEN_TAGS` and `SPECIMEN_SHARD`, which are
+    /// errors since they would make the run select other tiles. The
+    /// baseline, the state file and the JSON list need the `json` feature.
+    ///
+    /// - `SPECIMEN_REPORTER`: `text` or `annotations`
+    /// - `SPECIMEN_VERBOSE`: `1` or `true` to enable the verbose mode
+    /// - `SPECIMEN_SLOWEST`: the number of slowest tiles to list
+    /// - `SPECIMEN_SLOW`: the slowness threshold, e.g. `500ms` or `2s`
+    /// - `SPECIMEN_HTML`: the path of the HTML report to write
+    /// - `SPECIMEN_DOC`: the path of the documentation to write
+    /// - `SPECIMEN_BASELINE`: the path of the baseline to compare the run to
+    /// - `SPECIMEN_SAVE_BASELINE`: the path where the baseline of the run is saved
+    /// - `SPECIMEN_STATE`: the path of the state file. An empty value
+    ///   disables it.
+    /// - `SPECIMEN_LAST_FAILED`: `1` or `true` to run only the tiles which
+    ///   failed in the last run. When it is set, even to `0`, the state file
+    ///   is `.specimen/last-failed.json` unless `SPECIMEN_STATE` is set.
+    ///   Otherwise no state is recorded, so the failures of a run can only be
+    ///   rerun if it had `SPECIMEN_LAST_FAILED=0` or `SPECIMEN_STATE` set.
+    /// - `SPECIMEN_ID`: comma-separated tile IDs, possibly shortened, to run
+    ///   only these tiles
+    /// - `SPECIMEN_FILTER`: a regex, to run only the tiles it matches
+    /// - `SPECIMEN_FOCUS`: comma-separated `path:line` locations of the nodules
+    ///   to focus, e.g. `spec/flag.yaml:12`
+    /// - `SPECIMEN_TAGS`: a tag expression, e.g. `smoke & !slow`
+    /// - `SPECIMEN_SHARD`: `k/n` to run only the k-th of n shards of the tiles
+    /// - `SPECIMEN_STRICT`: `1` or `true` to enable the strict mode, `0` or
+    ///   `false` to disable it. By default, it is enabled when `CI` is `true`.
+    /// - `SPECIMEN_EXPLAIN`: `1` or `true` to explain the selection of the
+    ///   nodules and the tiles without running any box
+    /// - `SPECIMEN_LIST`: `text` or `json` to list the tiles without running
+    ///   any box
+    /// - `SPECIMEN_SAMPLE`: the number of tiles to run from each slab which
+    ///   has more, drawn at random
+    /// - `SPECIMEN_SEED`: the seed of the samples, to replay them. The shards
+    ///   of a run must have the same seed, which is 0 if it is not set.
+    pub fn from_env() -> Options {
+        let mut options = Options::default();
+
+        if let Ok(value) = env::var("SPECIMEN_REPORTER") {
+            options.reporter = match value.as_str() {
+                "annotations" => Reporter::Annotations,
+                "text" | "" => Reporter::Text,
+                other => {
+                    options.diagnostic_vec.push(Diagnostic::option_warning(
+                        "SPECIMEN_REPORTER",
+                        &format!(
+                            "Unrecognized value \"{other}\". The text reporter has been used."
+                        ),
+                    ));
+                    Reporter::Text
+                }
+            };
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_VERBOSE") {
+            options.verbose = is_truthy(&value);
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_SLOWEST") {
+            match value.parse() {
+                Ok(count) => options.slowest_count = count,
+                Err(_) => options.diagnostic_vec.push(Diagnostic::option_warning(
+                    "SPECIMEN_SLOWEST",
+                    &format!("The value must be a number, got \"{value}\". It has been ignored."),
+                )),
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_SLOW") {
+            options.slow_threshold = parse_duration(&value);
+            if options.slow_threshold.is_none() {
+                options.diagnostic_vec.push(Diagnostic::option_warning(
+                    "SPECIMEN_SLOW",
+                    &format!(
+                        "The value must be a duration such as 500ms or 2s, got \"{value}\". It has been ignored."
+                    ),
+                ));
+            }
+        }
+
+        if let Some(value) = env::var_os("SPECIMEN_HTML") {
+            options.html_path = Some(PathBuf::from(value));
+        }
+
+        if let Some(value) = env::var_os("SPECIMEN_DOC") {
+            options.documentation_path = Some(PathBuf::from(value));
+        }
+
+        if let Some(value) = env::var_os("SPECIMEN_BASELINE") {
+            options.baseline_path = Some(PathBuf::from(value));
+        }
+
+        if let Some(value) = env::var_os("SPECIMEN_SAVE_BASELINE") {
+            options.save_baseline_path = Some(PathBuf::from(value));
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_LAST_FAILED") {
+            options.last_failed = is_truthy(&value);
+        }
+
+        // The state file is only written on request, so that the runs do
+        // not leave files in the current directory
+        options.state_path = match env::var_os("SPECIMEN_STATE") {
+            Some(value) if value.is_empty() => None,
+            Some(value) => Some(PathBuf::from(value)),
+            None if env::var_os("SPECIMEN_LAST_FAILED").is_some() => {
+                Some(PathBuf::from(DEFAULT_STATE_PATH))
+            }
+            None => None,
+        };
+
+        if let Ok(value) = env::var("SPECIMEN_ID") {
+            options.id_vec = value
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(Box::from)
+                .collect();
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_FILTER") {
+            match Regex::new(&value) {
+                Ok(filter) => options.filter = Some(filter),
+                Err(e) => options.diagnostic_vec.push(Diagnostic::option_error(
+                    "SPECIMEN_FILTER",
+                    &format!("The value must be a valid regex, got \"{value}\" ({e})"),
+                )),
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_FOCUS") {
+            for text in value.split(',').filter(|text| !text.trim().is_empty()) {
+                match FocusLocation::parse(text) {
+                    Some(location) => options.focus_location_vec.push(location),
+                    None => options.diagnostic_vec.push(Diagnostic::option_error(
+                        "SPECIMEN_FOCUS",
+                        &format!("The value must list path:line locations, got \"{text}\""),
+                    )),
+                }
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_TAGS") {
+            match TagExpression::parse(&value) {
+                Ok(expression) => options.tag_expression = Some(expression),
+                Err(e) => options.diagnostic_vec.push(Diagnostic::option_error(
+                    "SPECIMEN_TAGS",
+                    &format!(
+                        "The value must be a tag expression such as \"smoke & !slow\", got \"{value}\" ({e})"
+                    ),
+                )),
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_SHARD") {
+            options.shard = Shard::parse(&value);
+            if options.shard.is_none() {
+                options.diagnostic_vec.push(Diagnostic::option_error(
+                    "SPECIMEN_SHARD",
+                    &format!("The value must be k/n with 1 <= k <= n, got \"{value}\""),
+                ));
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_EXPLAIN") {
+            options.explain = is_truthy(&value);
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_LIST") {
+            options.list = match value.as_str() {
+                "text" => Some(ListFormat::Text),
+                "json" => Some(ListFormat::Json),
+                "" => None,
+                other => {
+                    options.diagnostic_vec.push(Diagnostic::option_warning(
+                        "SPECIMEN_LIST",
+                        &format!(
+                            "The value must be text or json, got \"{other}\". It has been ignored."
+                        ),
+                    ));
+                    None
+                }
+            };
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_SAMPLE") {
+            match value.parse() {
+                Ok(count) if count > 0 => options.sample_count = Some(count),
+                _ => options.diagnostic_vec.push(Diagnostic::option_warning(
+                    "SPECIMEN_SAMPLE",
+                    &format!(
+                        "The value must be a positive number, got \"{value}\". It has been ignored."
+                    ),
+                )),
+            }
+        }
+
+        if let Ok(value) = env::var("SPECIMEN_SEED") {
+            match value.parse() {
+                Ok(seed) => options.seed = Some(seed),
+                Err(_) => options.diagnostic_vec.push(Diagnostic::option_warning(
+                    "SPECIMEN_SEED",
+                    &format!("The value must be a number, got \"{value}\". It has been ignored."),
+                )),
+            }
+        }
+
+        options.strict = match env::var("SPECIMEN_STRICT") {
+            Ok(value) => is_truthy(&value),
+            Err(_) => env::var("CI").is_ok_and(|value| value == "true"),
+        };
+
+        options
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value, "1" | "true" | "yes" | "on")
+}
+
+/// Parses a duration written as a number followed by `ms` or `s`. A number
+/// without unit is a number of milliseconds.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (number, factor) = if let Some(number) = text.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, 1.0)
+    } else {
+        (text, 0.001)
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    Duration::try_from_secs_f64(number * factor).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_the_tiles_into_shards() {
+        assert_eq!(Shard::parse("2/3"), Some(Shard { index: 2, count: 3 }));
+        assert_eq!(Shard::parse("0/3"), None);
+        assert_eq!(Shard::parse("4/3"), None);
+
+        let shard_vec: Vec<Shard> = (1..=3).map(|index| Shard { index, count: 3 }).collect();
+        for position in 0..10 {
+            let owner_count = shard_vec
+                .iter()
+                .filter(|shard| shard.contains(position))
+                .count();
+            assert_eq!(owner_count, 1);
+        }
+    }
+
+    #[test]
+    fn it_parses_focus_locations() {
+        assert_eq!(
+            FocusLocation::parse("spec/flag.yaml:12"),
+            Some(FocusLocation {
+                path: PathBuf::from("spec/flag.yaml"),
+                line: 12
+            })
+        );
+        assert_eq!(FocusLocation::parse("spec/flag.yaml"), None);
+        assert_eq!(FocusLocation::parse("spec/flag.yaml:0"), None);
+    }
+
+    #[test]
+    fn it_parses_durations() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("40"), Some(Duration::from_millis(40)));
+        assert_eq!(parse_duration("fast"), None);
+        assert_eq!(parse_duration("-1s"), None);
+    }
+}
@@ -0,0 +1,401 @@
+use crate::diagnostic::Diagnostic;
+use crate::diagnostic::Severity;
+use crate::flag::Date;
+use crate::FailStatus;
+use specimen__multistringmap::Dict;
+use specimen__writable::Writable;
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// The outcome of the run of a single tile.
+pub struct TileReport {
+    pub file_path: Rc<str>,
+    /// The location of the slab the tile comes from
+    pub slab_location: Box<str>,
+    /// The index of the tile among the tiles of its slab
+    pub index: usize,
+    /// The display name of the tile, see `Nodule::get_tile_name`
+    pub name: Box<str>,
+    /// The stable ID of the tile, see `TileIdentifier`
+    pub id: Box<str>,
+    /// The data passed to the test box
+    pub data: Dict,
+    pub status: FailStatus,
+    pub message: Box<str>,
+    /// The time spent in the test box, measured with a monotonic clock
+    pub duration: Duration,
+    pub slow_threshold: Option<Duration>,
+}
+
+impl TileReport {
+    /// returns a passing tile of the slab at line 1 of the file, with no data
+    #[cfg(test)]
+    pub fn new(file_path: &str, index: usize, status: FailStatus) -> Self {
+        TileReport {
+            file_path: file_path.into(),
+            slab_location: format!("{file_path}:1:1").into(),
+            index,
+            name: "".into(),
+            id: "".into(),
+            data: Dict::new(),
+            status,
+            message: "".into(),
+            duration: Duration::default(),
+            slow_threshold: None,
+        }
+    }
+
+    /// returns the word used to prefix the failure lines, if the tile failed.
+    pub fn failure_word(&self) -> Option<&'static str> {
+        match self.status {
+            FailStatus::Pristine => None,
+            FailStatus::Failed => Some("FAIL"),
+            FailStatus::Aborted => Some("ABORT"),
+            FailStatus::Panicked => Some("PANIC"),
+            FailStatus::ExpectedFailure => None,
+            FailStatus::UnexpectedPass => Some("XPASS"),
+        }
+    }
+
+    /// returns true if the tile took longer than its slowness threshold.
+    pub fn is_slow(&self) -> bool {
+        self.slow_threshold
+            .is_some_and(|threshold| self.duration > threshold)
+    }
+
+    /// returns the value of the `box` key of the tile, if any.
+    pub fn box_name(&self) -> Option<&str> {
+        self.data.get("box").map(|name| &**name)
+    }
+
+    /// returns the ID of the tile, as `#id`, followed by its name, each
+    /// preceded by a space.
+    pub fn label_suffix(&self) -> String {
+        let mut label = String::new();
+        if !self.id.is_empty() {
+            label.push_str(&format!(" #{}", self.id));
+        }
+        if !self.name.is_empty() {
+            label.push_str(&format!(" {}", self.name));
+        }
+        label
+    }
+}
+
+/// The number of hexadecimal digits of a tile ID
+const ID_LENGTH: usize = 12;
+
+/// Gives each tile an ID derived from its content, see
+/// `Nodule::get_tile_key`, so that it does not change when lines are
+/// inserted in the file. Tiles sharing the same key are told apart by their
+/// order of appearance.
+#[derive(Default)]
+pub struct TileIdentifier {
+    occurrence_map: HashMap<String, usize>,
+}
+
+impl TileIdentifier {
+    pub fn identify(&mut self, tile_key: &str) -> Box<str> {
+        let occurrence = self.occurrence_map.entry(tile_key.to_owned()).or_default();
+        *occurrence += 1;
+        let hash = if *occurrence > 1 {
+            fnv1a(&format!("{tile_key}\n#{occurrence}"))
+        } else {
+            fnv1a(tile_key)
+        };
+        format!("{hash:016x}")[..ID_LENGTH].into()
+    }
+}
+
+/// The 64-bit FNV-1a hash, which unlike the hasher of the standard library
+/// is guaranteed to give the same result across versions and platforms
+pub fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// returns true if the tile ID matches the ID given by the user, which may
+/// be shortened and preceded by `#`.
+pub fn id_matches(id: &str, requested_id: &str) -> bool {
+    let requested_id = requested_id.trim().trim_start_matches('#');
+    !requested_id.is_empty() && id.starts_with(requested_id)
+}
+
+/// A nodule which has been skipped, along with its descendants.
+pub struct SkipReport {
+    pub file_path: Rc<str>,
+    pub location: Box<str>,
+    /// The value of the `box` key of the nodule, if any
+    pub box_name: Option<Box<str>>,
+    /// The `reason` attribute of the PENDING flag
+    pub reason: Option<Box<str>>,
+    /// The `until` attribute of the PENDING flag
+    pub until: Option<Date>,
+    /// true if the `until` date has passed, which makes the run fail
+    pub expired: bool,
+}
+
+impl SkipReport {
+    /// returns the reason and the date of the PENDING flag, e.g.
+    /// `upstream bug (until 2026-12-01)`
+    pub fn describe(&self) -> String {
+        match (&self.reason, self.until) {
+            (Some(reason), Some(until)) => format!("{reason} (until {until})"),
+            (Some(reason), None) => reason.to_string(),
+            (None, Some(until)) => format!("until {until}"),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// A slab whose tiles have been skipped because one of its `when`
+/// conditions does not hold.
+pub struct ConditionSkipReport {
+    pub location: Box<str>,
+    /// Why the condition does not hold, e.g. `the environment variable
+    /// DB_URL is not set`
+    pub reason: Box<str>,
+    pub tile_count: usize,
+}
+
+/// A slab of which only a random sample of the tiles has been run.
+pub struct SampleReport {
+    pub location: Box<str>,
+    /// The number of tiles of the slab
+    pub total_count: usize,
+    /// The indices of the tiles which have been run, in increasing order
+    pub index_vec: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct Report {
+    pub diagnostic_vec: Vec<Diagnostic>,
+    pub tile_vec: Vec<TileReport>,
+    pub focus_count: usize,
+    /// The locations of all the nodes with a FOCUS flag, including those
+    /// whose flag is ignored in favor of their focused descendants
+    pub focus_location_vec: Vec<Box<str>>,
+    /// The number of pending nodes in the part of the tree selected by the
+    /// FOCUS flags
+    pub skip_count: usize,
+    /// All the pending nodes, including those outside of the focused nodes
+    pub skip_vec: Vec<SkipReport>,
+    /// The number of matrix values marked with `!FOCUS`
+    pub focused_value_count: usize,
+    /// The number of matrix values marked with `!PENDING`
+    pub pending_value_count: usize,
+    /// The number of selected tiles whose tags did not match the tag
+    /// expression
+    pub tag_skip_count: usize,
+    pub condition_skip_vec: Vec<ConditionSkipReport>,
+    /// The seed of the samples, which replays them when passed to a run
+    pub seed: u64,
+    pub sample_vec: Vec<SampleReport>,
+    /// The number of selected tiles which did not match the filter
+    pub filtered_count: usize,
+    pub duration: Duration,
+    /// true in strict mode, where an unexpected pass makes the run fail
+    pub strict: bool,
+}
+
+impl Report {
+    pub fn count(&self, status: FailStatus) -> usize {
+        self.tile_vec.iter().filter(|t| t.status == status).count()
+    }
+
+    /// returns true if the tile makes the run fail. An unexpected pass only
+    /// does in strict mode.
+    pub fn is_tile_failure(&self, tile: &TileReport) -> bool {
+        match tile.status {
+            FailStatus::UnexpectedPass => self.strict,
+            status => status.is_failure(),
+        }
+    }
+
+    /// returns true if all the tiles passed, no PENDING flag expired and no
+    /// error was issued. In strict mode, no XFAIL tile must have passed.
+    pub fn is_success(&self) -> bool {
+        self.tile_vec.iter().all(|tile| !self.is_tile_failure(tile))
+            && self.skip_vec.iter().all(|skip| !skip.expired)
+            && self
+                .diagnostic_vec
+                .iter()
+                .all(|diagnostic| diagnostic.severity != Severity::Error)
+    }
+}
+
+pub fn write_diagnostics(diagnostic_slice: &[Diagnostic], stdout: &mut Writable) -> io::Result<()> {
+    for diagnostic in diagnostic_slice.iter() {
+        writeln!(stdout, "{diagnostic}")?;
+    }
+    Ok(())
+}
+
+pub fn write_failures(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for tile in report.tile_vec.iter() {
+        if let Some(word) = tile.failure_word() {
+            writeln!(
+                stdout,
+                "{word}[{}][{}]: {}",
+                tile.slab_location, tile.index, tile.message
+            )?;
+        }
+    }
+    for skip in report.skip_vec.iter().filter(|skip| skip.expired) {
+        writeln!(
+            stdout,
+            "EXPIRED[{}]: The PENDING flag has expired: {}",
+            skip.location,
+            skip.describe()
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the reasons of the pending nodules which have one, and of the
+/// slabs skipped by their conditions.
+pub fn write_pending(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for skip in report.skip_vec.iter() {
+        if !skip.expired && skip.reason.is_some() {
+            writeln!(stdout, "PENDING[{}]: {}", skip.location, skip.describe())?;
+        }
+    }
+    for skip in report.condition_skip_vec.iter() {
+        writeln!(
+            stdout,
+            "SKIPPED[{}]: {} ({} tile(s))",
+            skip.location, skip.reason, skip.tile_count
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the location of each focused and pending node, along with the
+/// reason of the pending nodes, e.g. `pending: spec/x.yaml:40:8 (upstream bug)`.
+/// It replaces the PENDING lines in verbose mode.
+pub fn write_flag_locations(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for location in report.focus_location_vec.iter() {
+        writeln!(stdout, "focused: {location}")?;
+    }
+    for skip in report.skip_vec.iter().filter(|skip| !skip.expired) {
+        let description = skip.describe();
+        if description.is_empty() {
+            writeln!(stdout, "pending: {}", skip.location)?;
+        } else {
+            writeln!(stdout, "pending: {} ({description})", skip.location)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the ID and the name of the failed tiles, after the summary, e.g.
+/// `  FAIL[zoo.yaml:3:8][1] #b681b2fe3a54 zoo > animal matrix [animal=cat]`
+pub fn write_failed_tiles(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    let mut failed_iter = report
+        .tile_vec
+        .iter()
+        .filter(|tile| report.is_tile_failure(tile))
+        .filter_map(|tile| Some((tile.failure_word()?, tile)))
+        .peekable();
+    if failed_iter.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(stdout, "Failed tiles:")?;
+    for (word, tile) in failed_iter {
+        writeln!(
+            stdout,
+            "  {word}[{}][{}]{}",
+            tile.slab_location,
+            tile.index,
+            tile.label_suffix()
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the "Encountered ...", "Filtered out ..." and "Ran ..." lines,
+/// followed by the outcome of the run.
+pub fn write_summary(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    let mut message_vec = vec![];
+    if report.focus_count > 0 {
+        message_vec.push(format!("{} focused node(s)", report.focus_count));
+    }
+    if report.skip_count > 0 {
+        message_vec.push(format!("{} pending node(s)", report.skip_count));
+    }
+    if report.focused_value_count > 0 {
+        message_vec.push(format!("{} focused value(s)", report.focused_value_count));
+    }
+    if report.pending_value_count > 0 {
+        message_vec.push(format!("{} pending value(s)", report.pending_value_count));
+    }
+    let condition_skip_count: usize = report
+        .condition_skip_vec
+        .iter()
+        .map(|skip| skip.tile_count)
+        .sum();
+    if condition_skip_count > 0 {
+        message_vec.push(format!(
+            "{condition_skip_count} tile(s) skipped by condition"
+        ));
+    }
+    if report.tag_skip_count > 0 {
+        message_vec.push(format!("{} tile(s) skipped by tag", report.tag_skip_count));
+    }
+    if let Some(last_message) = message_vec.pop() {
+        if message_vec.is_empty() {
+            writeln!(stdout, "Encountered {last_message}")?;
+        } else {
+            writeln!(
+                stdout,
+                "Encountered {} and {last_message}",
+                message_vec.join(", ")
+            )?;
+        }
+    }
+    if report.filtered_count > 0 {
+        writeln!(stdout, "Filtered out {} tile(s)", report.filtered_count)?;
+    }
+    for sample in report.sample_vec.iter() {
+        let index_vec: Vec<String> = sample.index_vec.iter().map(usize::to_string).collect();
+        writeln!(
+            stdout,
+            "Sampled {} of {} tile(s) of {} with the seed {}: {}",
+            sample.index_vec.len(),
+            sample.total_count,
+            sample.location,
+            report.seed,
+            index_vec.join(", ")
+        )?;
+    }
+
+    let outcome = if report.is_success() {
+        "SUCCESS"
+    } else {
+        "FAILURE"
+    };
+
+    write!(
+        stdout,
+        "Ran {} tiles in {}ms\n\
+        {} -- {} Passed | {} Failed | {} Aborted | {} Panicked",
+        report.tile_vec.len(),
+        report.duration.as_millis(),
+        outcome,
+        report.count(FailStatus::Pristine),
+        report.count(FailStatus::Failed),
+        report.count(FailStatus::Aborted),
+        report.count(FailStatus::Panicked),
+    )?;
+    // The XFAIL counts are only shown when the run has such tiles
+    let xfail_count = report.count(FailStatus::ExpectedFailure);
+    let xpass_count = report.count(FailStatus::UnexpectedPass);
+    if xfail_count + xpass_count > 0 {
+        write!(stdout, " | {xfail_count} XFAIL | {xpass_count} XPASS")?;
+    }
+    writeln!(stdout)
+}
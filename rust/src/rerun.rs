@@ -0,0 +1,59 @@
+//! The state file records the IDs of the tiles which failed, so that the next
+//! run can be limited to them. The IDs do not depend on the line numbers, so
+//! the tiles are still found after their YAML has moved.
+
+use crate::json;
+use crate::report::Report;
+#[cfg(feature = "json")]
+use serde::Deserialize;
+#[cfg(feature = "json")]
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The content of the state file
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+struct State {
+    failed: Vec<Box<str>>,
+}
+
+/// Reads the IDs of the tiles which failed in the last run.
+pub fn read_failed(path: &Path) -> io::Result<HashSet<Box<str>>> {
+    let state: State = json::from_str(&fs::read_to_string(path)?)?;
+    Ok(state.failed.into_iter().collect())
+}
+
+/// Writes the IDs of the tiles which failed. A tile which did not run
+/// keeps the state it had in the previous state file, so that running a part
+/// of the tiles does not forget the failures of the others. The tiles which no
+/// longer exist are dropped.
+pub fn write_failed(
+    path: &Path,
+    previous_failed_set: &HashSet<Box<str>>,
+    id_vec: &[Box<str>],
+    report: &Report,
+) -> io::Result<()> {
+    let ran_set: HashSet<&str> = report.tile_vec.iter().map(|tile| &*tile.id).collect();
+    let failed_set: HashSet<&str> = report
+        .tile_vec
+        .iter()
+        .filter(|tile| report.is_tile_failure(tile))
+        .map(|tile| &*tile.id)
+        .collect();
+    let failed_vec = id_vec
+        .iter()
+        .filter(|id| {
+            failed_set.contains(&***id)
+                || !ran_set.contains(&***id) && previous_failed_set.contains(*id)
+        })
+        .cloned()
+        .collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let state = State { failed: failed_vec };
+    fs::write(path, json::to_string_pretty(&state)? + "\n")
+}
@@ -0,0 +1,79 @@
+//! Seeded random sampling of the tiles of the slabs whose product is large.
+//! The sample size is set with the `sample` key, inherited by the
+//! descendants, or with the `sample_count` option. The sample of a slab only
+//! depends on the seed and on the tree path of the slab, so passing the seed
+//! printed in the report replays the same tiles.
+
+use crate::report::fnv1a;
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+/// The seed of the sharded runs which are not given one. The shards are run
+/// by separate processes, which must draw the same samples so that the
+/// shards partition them.
+pub const SHARD_SEED: u64 = 0;
+
+/// returns a seed for the runs which are not given one
+pub fn random_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    SplitMix64(nanos as u64).next()
+}
+
+/// returns the seed of the sample of a slab, derived from the seed of the
+/// run and the key of the slab, so that the slabs get different samples
+pub fn slab_seed(seed: u64, slab_key: &str) -> u64 {
+    SplitMix64(seed ^ fnv1a(slab_key)).next()
+}
+
+/// The SplitMix64 generator, which unlike the random generators of the
+/// crates gives the same sequence across versions and platforms
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// returns a number in `0..bound`
+    fn below(&mut self, bound: usize) -> usize {
+        ((u128::from(self.next()) * bound as u128) >> 64) as usize
+    }
+}
+
+/// returns `sample_count` distinct indices in `0..total_count`, in
+/// increasing order, drawn with Robert Floyd's algorithm
+pub fn sample_indices(sample_count: usize, total_count: usize, seed: u64) -> Vec<usize> {
+    let mut generator = SplitMix64(seed);
+    let mut index_set = HashSet::new();
+    for j in total_count.saturating_sub(sample_count)..total_count {
+        let index = generator.below(j + 1);
+        if !index_set.insert(index) {
+            index_set.insert(j);
+        }
+    }
+    let mut index_vec: Vec<usize> = index_set.into_iter().collect();
+    index_vec.sort_unstable();
+    index_vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_samples_distinct_indices() {
+        let index_vec = sample_indices(5, 20, 42);
+        assert_eq!(index_vec.len(), 5);
+        assert!(index_vec.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(index_vec.iter().all(|index| *index < 20));
+        assert_eq!(sample_indices(5, 20, 42), index_vec);
+        assert_ne!(sample_indices(5, 20, 43), index_vec);
+        assert_eq!(sample_indices(4, 4, 42), [0, 1, 2, 3]);
+    }
+}
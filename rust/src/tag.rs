@@ -0,0 +1,136 @@
+//! Tag expressions select the tiles by the tags of their slab, set with the
+//! `tags` key. A tag expression combines tag names with `&` (and), `|` (or),
+//! `!` (not) and parentheses, e.g. `smoke & !slow`. `&` binds tighter than
+//! `|`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagExpression {
+    Tag(Box<str>),
+    Not(Box<TagExpression>),
+    And(Box<TagExpression>, Box<TagExpression>),
+    Or(Box<TagExpression>, Box<TagExpression>),
+}
+
+impl TagExpression {
+    pub fn parse(text: &str) -> Result<TagExpression, String> {
+        let mut parser = Parser {
+            token_vec: tokenize(text),
+            position: 0,
+        };
+        let expression = parser.parse_or()?;
+        match parser.token_vec.get(parser.position) {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected \"{token}\"")),
+        }
+    }
+
+    /// returns true if the tags satisfy the expression
+    pub fn matches(&self, tag_slice: &[Box<str>]) -> bool {
+        match self {
+            TagExpression::Tag(tag) => tag_slice.contains(tag),
+            TagExpression::Not(expression) => !expression.matches(tag_slice),
+            TagExpression::And(left, right) => left.matches(tag_slice) && right.matches(tag_slice),
+            TagExpression::Or(left, right) => left.matches(tag_slice) || right.matches(tag_slice),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Box<str>> {
+    let mut token_vec = Vec::new();
+    let mut tag = String::new();
+    for c in text.chars() {
+        if matches!(c, '&' | '|' | '!' | '(' | ')') || c.is_whitespace() {
+            if !tag.is_empty() {
+                token_vec.push(std::mem::take(&mut tag).into());
+            }
+            if !c.is_whitespace() {
+                token_vec.push(c.to_string().into());
+            }
+        } else {
+            tag.push(c);
+        }
+    }
+    if !tag.is_empty() {
+        token_vec.push(tag.into());
+    }
+    token_vec
+}
+
+struct Parser {
+    token_vec: Vec<Box<str>>,
+    position: usize,
+}
+
+impl Parser {
+    fn next_is(&self, token: &str) -> bool {
+        self.token_vec
+            .get(self.position)
+            .is_some_and(|next| &**next == token)
+    }
+
+    fn parse_or(&mut self) -> Result<TagExpression, String> {
+        let mut expression = self.parse_and()?;
+        while self.next_is("|") {
+            self.position += 1;
+            expression = TagExpression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<TagExpression, String> {
+        let mut expression = self.parse_not()?;
+        while self.next_is("&") {
+            self.position += 1;
+            expression = TagExpression::And(Box::new(expression), Box::new(self.parse_not()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> Result<TagExpression, String> {
+        let token = match self.token_vec.get(self.position) {
+            Some(token) => token.clone(),
+            None => return Err("unexpected end of the expression".into()),
+        };
+        self.position += 1;
+        match &*token {
+            "!" => Ok(TagExpression::Not(Box::new(self.parse_not()?))),
+            "(" => {
+                let expression = self.parse_or()?;
+                if !self.next_is(")") {
+                    return Err("missing \")\"".into());
+                }
+                self.position += 1;
+                Ok(expression)
+            }
+            "&" | "|" | ")" => Err(format!("unexpected \"{token}\"")),
+            _ => Ok(TagExpression::Tag(token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(text: &str) -> Vec<Box<str>> {
+        text.split_whitespace().map(Box::from).collect()
+    }
+
+    #[test]
+    fn it_evaluates_tag_expressions() {
+        let expression = TagExpression::parse("smoke & !slow | (db & !(slow))").unwrap();
+        assert!(expression.matches(&tags("smoke")));
+        assert!(!expression.matches(&tags("smoke slow")));
+        assert!(expression.matches(&tags("db")));
+        assert!(!expression.matches(&tags("db slow")));
+        assert!(!expression.matches(&tags("")));
+    }
+
+    #[test]
+    fn it_rejects_invalid_expressions() {
+        assert!(TagExpression::parse("").is_err());
+        assert!(TagExpression::parse("smoke &").is_err());
+        assert!(TagExpression::parse("(smoke").is_err());
+        assert!(TagExpression::parse("smoke slow").is_err());
+    }
+}
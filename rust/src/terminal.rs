@@ -0,0 +1,160 @@
+//! The terminal reporter prints the progress of the run while the tiles are
+//! running. When the output is not a terminal, it falls back to plain text
+//! lines and no progress indicator is displayed. Colors are disabled when the
+//! `NO_COLOR` environment variable is set.
+
+use crate::report::TileReport;
+use crate::FailStatus;
+use specimen__writable::Writable;
+use std::env;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The progress indicator is only displayed for runs lasting longer than this.
+const PROGRESS_DELAY: Duration = Duration::from_secs(1);
+/// The minimum time between two updates of the progress indicator.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+const RESET: &str = "\x1b[0m";
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+pub struct Terminal {
+    verbose: bool,
+    color: bool,
+    progress: bool,
+    tile_total: usize,
+    tile_done: usize,
+    start: Instant,
+    last_progress: Option<Instant>,
+}
+
+impl Terminal {
+    pub fn new(verbose: bool, tile_total: usize, stdout: &Writable) -> Terminal {
+        let is_terminal = stdout.is_terminal();
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Terminal {
+            verbose,
+            color: is_terminal && !no_color,
+            progress: is_terminal,
+            tile_total,
+            tile_done: 0,
+            start: Instant::now(),
+            last_progress: None,
+        }
+    }
+
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color {
+            format!("{color}{text}{RESET}")
+        } else {
+            text.to_owned()
+        }
+    }
+
+    /// Erases the progress indicator, if it is displayed.
+    fn clear_progress(&mut self, stdout: &mut Writable) -> io::Result<()> {
+        if self.last_progress.take().is_some() {
+            write!(stdout, "{CLEAR_LINE}")?;
+        }
+        Ok(())
+    }
+
+    fn write_progress(&mut self, stdout: &mut Writable) -> io::Result<()> {
+        let now = Instant::now();
+        let elapsed = now - self.start;
+        if elapsed < PROGRESS_DELAY
+            || self.tile_done == 0
+            || self
+                .last_progress
+                .is_some_and(|t| now - t < PROGRESS_INTERVAL)
+        {
+            return Ok(());
+        }
+        let remaining = self.tile_total.saturating_sub(self.tile_done);
+        let eta = elapsed.mul_f64(remaining as f64 / self.tile_done as f64);
+        write!(
+            stdout,
+            "{CLEAR_LINE}[{}/{}] {}% ETA {}s",
+            self.tile_done,
+            self.tile_total,
+            100 * self.tile_done / self.tile_total.max(1),
+            eta.as_secs(),
+        )?;
+        stdout.flush()?;
+        self.last_progress = Some(now);
+        Ok(())
+    }
+
+    pub fn tile_end(&mut self, tile: &TileReport, stdout: &mut Writable) -> io::Result<()> {
+        self.tile_done += 1;
+
+        if self.verbose {
+            self.clear_progress(stdout)?;
+            let (word, color) = match tile.status {
+                FailStatus::Pristine => ("PASS", GREEN),
+                FailStatus::Failed => ("FAIL", RED),
+                FailStatus::Aborted => ("ABORT", YELLOW),
+                FailStatus::Panicked => ("PANIC", MAGENTA),
+                FailStatus::ExpectedFailure => ("XFAIL", GREEN),
+                FailStatus::UnexpectedPass => ("XPASS", YELLOW),
+            };
+            let mut line = format!(
+                "{}[{}][{}]{}",
+                self.paint(word, color),
+                tile.slab_location,
+                tile.index,
+                tile.label_suffix()
+            );
+            if tile.status != FailStatus::Pristine {
+                line = format!("{line}: {}", tile.message);
+            }
+            writeln!(stdout, "{line}")?;
+        }
+
+        if self.progress {
+            self.write_progress(stdout)?;
+        }
+        Ok(())
+    }
+
+    /// Erases the progress indicator once all the tiles have run.
+    pub fn finish(&mut self, stdout: &mut Writable) -> io::Result<()> {
+        self.clear_progress(stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_falls_back_to_plain_lines_outside_of_a_terminal() {
+        let mut stdout = Writable::Vec(Vec::new());
+        let mut terminal = Terminal::new(true, 2, &stdout);
+        for (index, status) in [FailStatus::Pristine, FailStatus::Failed]
+            .into_iter()
+            .enumerate()
+        {
+            let tile = TileReport {
+                message: "failure".into(),
+                ..TileReport::new("a.yaml", index, status)
+            };
+            terminal.tile_end(&tile, &mut stdout).unwrap();
+        }
+        terminal.finish(&mut stdout).unwrap();
+
+        let Writable::Vec(buffer) = stdout else {
+            unreachable!()
+        };
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "PASS[a.yaml:1:1][0]\nFAIL[a.yaml:1:1][1]: failure\n"
+        );
+    }
+}
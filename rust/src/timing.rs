@@ -0,0 +1,109 @@
+//! The reports about the time spent in the test boxes: the list of the
+//! slowest tiles, and the tiles which took longer than their slowness
+//! threshold.
+
+use crate::report::Report;
+use specimen__writable::Writable;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Writes the `count` slowest tiles, from the slowest to the fastest.
+pub fn write_slowest(report: &Report, count: usize, stdout: &mut Writable) -> io::Result<()> {
+    if count == 0 || report.tile_vec.is_empty() {
+        return Ok(());
+    }
+    let mut tile_vec: Vec<_> = report.tile_vec.iter().collect();
+    tile_vec.sort_by_key(|tile| std::cmp::Reverse(tile.duration));
+
+    writeln!(stdout, "Slowest tiles:")?;
+    for tile in tile_vec.into_iter().take(count) {
+        writeln!(
+            stdout,
+            "  {:>10} [{}][{}]{}",
+            format_duration(tile.duration),
+            tile.slab_location,
+            tile.index,
+            tile.label_suffix()
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a SLOW line for each tile which took longer than its threshold.
+pub fn write_slow(report: &Report, stdout: &mut Writable) -> io::Result<()> {
+    for tile in report.tile_vec.iter().filter(|tile| tile.is_slow()) {
+        writeln!(
+            stdout,
+            "SLOW[{}][{}]{}: {}",
+            tile.slab_location,
+            tile.index,
+            tile.label_suffix(),
+            slow_message(tile.duration, tile.slow_threshold.unwrap_or_default())
+        )?;
+    }
+    Ok(())
+}
+
+pub fn slow_message(duration: Duration, threshold: Duration) -> String {
+    format!(
+        "took {}, which is more than the {} threshold",
+        format_duration(duration),
+        format_duration(threshold)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::TileReport;
+    use crate::FailStatus;
+
+    fn tile(index: usize, millis: u64, slow_threshold: Option<u64>) -> TileReport {
+        TileReport {
+            duration: Duration::from_millis(millis),
+            slow_threshold: slow_threshold.map(Duration::from_millis),
+            ..TileReport::new("a.yaml", index, FailStatus::Pristine)
+        }
+    }
+
+    fn output(write: impl FnOnce(&mut Writable) -> io::Result<()>) -> String {
+        let mut stdout = Writable::Vec(Vec::new());
+        write(&mut stdout).unwrap();
+        let Writable::Vec(buffer) = stdout else {
+            unreachable!()
+        };
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn it_writes_the_slowest_tiles_first() {
+        let report = Report {
+            tile_vec: vec![tile(0, 2, None), tile(1, 7, None), tile(2, 5, None)],
+            ..Report::default()
+        };
+        assert_eq!(
+            output(|stdout| write_slowest(&report, 2, stdout)),
+            "Slowest tiles:\n       \
+            7.0ms [a.yaml:1:1][1]\n       \
+            5.0ms [a.yaml:1:1][2]\n"
+        );
+        assert_eq!(output(|stdout| write_slowest(&report, 0, stdout)), "");
+    }
+
+    #[test]
+    fn it_writes_the_tiles_over_their_threshold() {
+        let report = Report {
+            tile_vec: vec![tile(0, 5, Some(5)), tile(1, 6, Some(5)), tile(2, 9, None)],
+            ..Report::default()
+        };
+        assert_eq!(
+            output(|stdout| write_slow(&report, stdout)),
+            "SLOW[a.yaml:1:1][1]: took 6.0ms, which is more than the 5.0ms threshold\n"
+        );
+    }
+}
@@ -1,7 +1,6 @@
 use crate::nodule::Nodule;
 use specimen__focustree as focustree;
-use specimen__writable::Writable;
-use std::io::Write;
+use specimen__multistringmap::Mark;
 
 // This file implements the focustree::Tree trait for the Nodule struct and the NoduleRoot type.
 
@@ -25,10 +24,14 @@ impl<'a> focustree::Tree<Nodule<'a>> for Nodule<'a> {
         (*self).clone()
     }
 
-    fn warning(&self, message: &str, stdout: &mut Writable) {
-        let res = write!(stdout, "Warning({}): {}\n", self.get_location(), message);
-        if let Err(e) = res {
-            panic!("Error: {}", e);
-        }
+    fn get_value_flag_vec(&self) -> Vec<focustree::Flag> {
+        self.get_value_mark_vec()
+            .into_iter()
+            .map(|mark| match mark {
+                Mark::Focus => focustree::Flag::Focus,
+                Mark::Skip => focustree::Flag::Skip,
+                Mark::None => focustree::Flag::None,
+            })
+            .collect()
     }
 }
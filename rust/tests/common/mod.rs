@@ -0,0 +1,65 @@
+//! The helpers shared by the integration tests. Each test file uses a part
+//! of them only.
+#![allow(dead_code)]
+
+use specimen::options::Options;
+use specimen::RunResult;
+use specimen::Writable;
+
+/// returns the text written to a `Writable::Vec`
+pub fn output(stdout: Writable) -> String {
+    let Writable::Vec(buffer) = stdout else {
+        unreachable!()
+    };
+    String::from_utf8(buffer).unwrap()
+}
+
+/// returns a spec file named `zoo.yaml`
+pub fn zoo_file(content: &str) -> specimen::file::File {
+    specimen::file::File {
+        path: "zoo.yaml".into(),
+        content: content.into(),
+    }
+}
+
+/// Runs the box on the spec files, and returns the result of the run along
+/// with its output
+pub fn run_files(
+    file_slice: &[specimen::file::File],
+    options: &Options,
+    test_box: &mut dyn FnMut(&specimen::Dict) -> Result<(), Box<str>>,
+) -> (RunResult, String) {
+    let mut stdout = Writable::Vec(Vec::new());
+    let result =
+        specimen::ioless_run_with_result(test_box, file_slice, options, &mut stdout).unwrap();
+    (result, output(stdout))
+}
+
+/// Runs the box on a single spec file named `zoo.yaml`
+pub fn run_zoo(
+    content: &str,
+    options: &Options,
+    test_box: &mut dyn FnMut(&specimen::Dict) -> Result<(), Box<str>>,
+) -> (RunResult, String) {
+    run_files(&[zoo_file(content)], options, test_box)
+}
+
+/// Runs a box which records the `animal` of each tile and passes, on the
+/// spec files
+pub fn run_animal_files(
+    file_slice: &[specimen::file::File],
+    options: &Options,
+) -> (RunResult, Vec<String>, String) {
+    let mut animal_vec = Vec::new();
+    let (result, output) = run_files(file_slice, options, &mut |tile| {
+        animal_vec.push(tile["animal"].to_string());
+        Ok(())
+    });
+    (result, animal_vec, output)
+}
+
+/// Runs a box which records the `animal` of each tile and passes, on a
+/// single spec file named `zoo.yaml`
+pub fn run_animals(content: &str, options: &Options) -> (RunResult, Vec<String>, String) {
+    run_animal_files(&[zoo_file(content)], options)
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use specimen::diagnostic::Diagnostic;
+use specimen::diagnostic::Severity;
+use specimen::options::Options;
+
+#[test]
+fn test_diagnostics() {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+            - flag: FOCUS PENDING\n  \
+            - flag: SOON\n",
+        &Options::default(),
+        &mut |_tile| Ok(()),
+    );
+
+    assert!(result.success);
+    assert_eq!(
+        result.diagnostic_vec,
+        vec![
+            Diagnostic {
+                severity: Severity::Warning,
+                message: "Both FOCUS and PENDING flags have been found among the flags of a node. PENDING has been kept.".into(),
+                file_path: "zoo.yaml".into(),
+                line: 2,
+                column: 11,
+            },
+            Diagnostic {
+                severity: Severity::Warning,
+                message: "Unrecognized all uppercase flag \"SOON\". It has been ignored.".into(),
+                file_path: "zoo.yaml".into(),
+                line: 3,
+                column: 11,
+            },
+        ]
+    );
+
+    assert!(output.starts_with("Warning(zoo.yaml:2:11): Both FOCUS and PENDING"));
+}
+
+#[test]
+fn test_unreadable_baseline_diagnostic() {
+    let baseline_path = std::env::temp_dir().join(format!(
+        "specimen-missing-baseline-{}.json",
+        std::process::id()
+    ));
+    let options = Options {
+        reporter: specimen::options::Reporter::Annotations,
+        baseline_path: Some(baseline_path.clone()),
+        ..Options::default()
+    };
+    let (result, output) = common::run_zoo("animal: cat\n", &options, &mut |_tile| Ok(()));
+
+    assert!(result.success);
+    assert_eq!(result.diagnostic_vec.len(), 1);
+    assert_eq!(result.diagnostic_vec[0].severity, Severity::Warning);
+    assert_eq!(
+        &*result.diagnostic_vec[0].file_path,
+        &*baseline_path.to_string_lossy()
+    );
+    assert!(output.contains(",line=1,col=1::The baseline could not be read: "));
+}
+
+#[test]
+fn test_option_errors_stop_the_run() {
+    let mut options = Options {
+        diagnostic_vec: vec![
+            Diagnostic::option_warning("SPECIMEN_SEED", "The value must be a number"),
+            Diagnostic::option_error("SPECIMEN_SHARD", "The value must be k/n"),
+        ],
+        ..Options::default()
+    };
+    let (result, output) = common::run_zoo("animal: cat\n", &options, &mut |_tile| {
+        panic!("No box must run")
+    });
+
+    assert!(!result.success);
+    assert_eq!(result.diagnostic_vec, options.diagnostic_vec);
+    assert_eq!(
+        output,
+        "Warning(SPECIMEN_SEED): The value must be a number\n\
+        Error(SPECIMEN_SHARD): The value must be k/n\n\
+        FAILURE -- No tile has been run because of the errors in the options\n"
+    );
+
+    // The warnings alone do not stop the run
+    options.diagnostic_vec.pop();
+    options.reporter = specimen::options::Reporter::Annotations;
+    let (result, output) = common::run_zoo("animal: cat\n", &options, &mut |_tile| Ok(()));
+    assert!(result.success);
+    assert!(output.starts_with("::warning::SPECIMEN_SEED: The value must be a number\n"));
+}
+
+#[test]
+fn test_unwritable_files_diagnostics() {
+    let directory =
+        std::env::temp_dir().join(format!("specimen-missing-directory-{}", std::process::id()));
+    let options = Options {
+        html_path: Some(directory.join("report.html")),
+        documentation_path: Some(directory.join("doc.md")),
+        save_baseline_path: Some(directory.join("baseline.json")),
+        ..Options::default()
+    };
+    let (result, output) = common::run_zoo("animal: cat\n", &options, &mut |_tile| Ok(()));
+
+    assert!(result.success);
+    let message_vec: Vec<&str> = result
+        .diagnostic_vec
+        .iter()
+        .map(|diagnostic| diagnostic.message.split(':').next().unwrap())
+        .collect();
+    assert_eq!(
+        message_vec,
+        [
+            "The baseline could not be saved",
+            "The HTML report could not be written",
+            "The documentation could not be written",
+        ]
+    );
+    // The diagnostics are printed before the summary
+    let summary_position = output.find("SUCCESS -- ").unwrap();
+    assert!(output.rfind("Warning(").unwrap() < summary_position);
+}
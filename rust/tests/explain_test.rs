@@ -0,0 +1,56 @@
+mod common;
+
+use specimen::options::Options;
+use specimen::tag::TagExpression;
+
+#[test]
+fn test_explain() {
+    let (result, output) = common::run_zoo(
+        "name: zoo\n\
+                content:\n  \
+                  - name: horse\n    \
+                    animal: horse\n  \
+                  - name: small\n    \
+                    flag: FOCUS\n    \
+                    content:\n      \
+                      - tags: slow\n        \
+                        animal: snail\n      \
+                      - animal: [mouse, cat]\n      \
+                      - flag: PENDING reason=\"escaped\"\n        \
+                        animal: hamster\n",
+        &Options {
+            explain: true,
+            tag_expression: Some(TagExpression::parse("!slow").unwrap()),
+            ..Options::default()
+        },
+        &mut |_tile| panic!("No box must run"),
+    );
+
+    assert!(result.success);
+    let line_vec: Vec<String> = output
+        .lines()
+        .map(|line| {
+            // The IDs are not checked
+            match line.split_once(" #") {
+                Some((start, end)) => format!("{start} {}", end.split_once(' ').unwrap().1),
+                None => line.into(),
+            }
+        })
+        .collect();
+    assert_eq!(
+        line_vec,
+        [
+            "Explaining the selection, no box is run",
+            "zoo.yaml:1:4 (zoo): partly selected, contains focused nodes",
+            "  zoo.yaml:3:8 (horse): dropped, outside the focus",
+            "  zoo.yaml:5:8 (small): selected",
+            "    zoo.yaml:8:12 (#0): selected",
+            "      [0] zoo > small: dropped, excluded by the tags",
+            "    zoo.yaml:10:14 (#1): selected",
+            "      [0] zoo > small [animal=mouse]: selected",
+            "      [1] zoo > small [animal=cat]: selected",
+            "    zoo.yaml:11:12 (#2): dropped, pending: escaped",
+            "2 of 3 tile(s) would run",
+        ]
+    );
+}
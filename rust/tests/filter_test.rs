@@ -0,0 +1,40 @@
+mod common;
+
+use regex::Regex;
+use specimen::options::Options;
+
+fn run_zoo(filter: &str) -> (Vec<String>, String) {
+    let (result, animal_vec, output) = common::run_animals(
+        "name: zoo\n\
+            content:\n  \
+              - name: horse\n    \
+                animal: horse\n  \
+              - name: small animals\n    \
+                animal: [mouse, cat]\n  \
+              - name: bird\n    \
+                flag: PENDING\n    \
+                animal: parrot\n",
+        &Options {
+            filter: Some(Regex::new(filter).unwrap()),
+            ..Options::default()
+        },
+    );
+    assert!(result.success);
+    (animal_vec, output)
+}
+
+#[test]
+fn test_filter() {
+    // The filter is matched against the names
+    let (animal_vec, output) = run_zoo("small");
+    assert_eq!(animal_vec, ["mouse", "cat"]);
+    assert!(output.contains("Filtered out 1 tile(s)\nRan 2 tiles"));
+
+    // ... against the key=value pairs
+    assert_eq!(run_zoo("^animal=cat$").0, ["cat"]);
+
+    // ... and against the file paths, without selecting the PENDING nodes
+    let (animal_vec, output) = run_zoo(r"zoo\.yaml");
+    assert_eq!(animal_vec, ["horse", "mouse", "cat"]);
+    assert!(!output.contains("Filtered out"));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use specimen::options::FocusLocation;
+use specimen::options::Options;
+
+fn run(file_slice: &[specimen::file::File], location_vec: &[&str]) -> (Vec<String>, String) {
+    let (result, animal_vec, output) = common::run_animal_files(
+        file_slice,
+        &Options {
+            focus_location_vec: location_vec
+                .iter()
+                .map(|text| FocusLocation::parse(text).unwrap())
+                .collect(),
+            ..Options::default()
+        },
+    );
+    assert!(result.success);
+    (animal_vec, output)
+}
+
+fn run_zoo(location_vec: &[&str]) -> (Vec<String>, String) {
+    run(
+        &[specimen::file::File {
+            path: "../spec/zoo.yaml".into(),
+            content: "name: zoo\n\
+                content:\n  \
+                  - name: horse\n    \
+                    animal: horse\n  \
+                  - name: small animals\n    \
+                    content:\n      \
+                      - animal: mouse\n      \
+                      - animal: cat\n        \
+                        leg: \"4\"\n  \
+                  - flag: PENDING\n    \
+                    animal: parrot\n"
+                .into(),
+        }],
+        location_vec,
+    )
+}
+
+#[test]
+fn test_focus_location() {
+    // The nodule starting at the line is focused
+    let (animal_vec, output) = run_zoo(&["spec/zoo.yaml:5"]);
+    assert_eq!(animal_vec, ["mouse", "cat"]);
+    assert!(output.contains("Encountered 1 focused node(s)"));
+
+    // ... or else the innermost nodule containing the line
+    assert_eq!(run_zoo(&["spec/zoo.yaml:9"]).0, ["cat"]);
+    assert_eq!(
+        run_zoo(&["spec/zoo.yaml:4", "zoo.yaml:7"]).0,
+        ["horse", "mouse"]
+    );
+
+    // The pending nodules and the other files are reported
+    let (animal_vec, output) = run_zoo(&["spec/zoo.yaml:11", "other.yaml:1"]);
+    assert_eq!(animal_vec, ["horse", "mouse", "cat"]);
+    assert!(output
+        .contains("Warning(spec/zoo.yaml:11:1): The nodule at ../spec/zoo.yaml:10:8 is pending"));
+    assert!(output.contains("Warning(other.yaml:1:1): No nodule was found at this location"));
+}
+
+#[test]
+fn test_focus_location_after_a_nested_child() {
+    let file = specimen::file::File {
+        path: "zoo.yaml".into(),
+        content: "content:\n  \
+              - name: small animals\n    \
+                content:\n      \
+                  - animal: mouse\n      \
+                  - animal: cat\n    \
+                leg: \"4\"\n  \
+              - animal: horse\n"
+            .into(),
+    };
+    // The line of the leg key is after the last child of the small animals
+    assert_eq!(run(&[file], &["zoo.yaml:6"]).0, ["mouse", "cat"]);
+}
+
+#[test]
+fn test_focus_location_matching_several_files() {
+    let file = |path: &str, animal: &str| specimen::file::File {
+        path: path.into(),
+        content: format!("animal: {animal}\n").into(),
+    };
+    let file_array = [file("a/zoo.yaml", "cat"), file("b/zoo.yaml", "dog")];
+
+    let (animal_vec, output) = run(&file_array, &["zoo.yaml:1"]);
+    assert_eq!(animal_vec, ["cat", "dog"]);
+    assert!(output.contains(
+        "Warning(zoo.yaml:1:1): The path matches several spec files: a/zoo.yaml, b/zoo.yaml. It has been ignored."
+    ));
+
+    assert_eq!(run(&file_array, &["b/zoo.yaml:1"]).0, ["dog"]);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use specimen::options::Options;
+
+fn run_zoo(content: &str, options: &Options) -> (Vec<String>, String) {
+    let mut animal_vec = Vec::new();
+    let (_, output) = common::run_zoo(content, options, &mut |tile| {
+        animal_vec.push(tile["animal"].to_string());
+        Err("the animal escaped".into())
+    });
+    (animal_vec, output)
+}
+
+fn id_of(output: &str, animal: &str) -> String {
+    let line = output
+        .lines()
+        .find(|line| line.contains(&format!("animal={animal}")))
+        .unwrap();
+    let start = line.find(" #").unwrap() + 2;
+    line[start..start + 12].to_owned()
+}
+
+#[test]
+fn test_tile_id() {
+    let content = "name: zoo\n\
+        content:\n  \
+          - animal: [mouse, cat]\n";
+    let moved_content = "name: zoo\n\
+        content:\n  \
+          - name: horse\n    \
+            flag: PENDING\n    \
+            animal: horse\n  \
+          - animal: [cat, mouse]\n";
+    let (_, output) = run_zoo(content, &Options::default());
+    let (_, moved_output) = run_zoo(moved_content, &Options::default());
+
+    // The unnamed nodules are identified by their position among their
+    // siblings
+    let cat_id = id_of(&output, "cat");
+    assert_ne!(cat_id, id_of(&output, "mouse"));
+    assert_ne!(cat_id, id_of(&moved_output, "cat"));
+
+    let renamed_content = "name: zoo\n\n\
+        content:\n  \
+          - name: small\n    \
+            animal: [cat, mouse]\n";
+    let renamed_moved_content = "name: zoo\n\
+        content:\n  \
+          - name: horse\n    \
+            animal: horse\n  \
+          - name: small\n    \
+            animal: [mouse, cat]\n";
+    // The named nodules are identified by their name, whatever their line
+    // and the order of their values
+    let (_, output) = run_zoo(renamed_content, &Options::default());
+    let (_, moved_output) = run_zoo(renamed_moved_content, &Options::default());
+    let cat_id = id_of(&output, "cat");
+    assert_eq!(cat_id, id_of(&moved_output, "cat"));
+
+    // A shortened ID can be used to run a single tile
+    let options = Options {
+        id_vec: vec![format!("#{}", &cat_id[..6]).into()],
+        ..Options::default()
+    };
+    let (animal_vec, _) = run_zoo(renamed_moved_content, &options);
+    assert_eq!(animal_vec, ["cat"]);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use specimen::options::ListFormat;
+use specimen::options::Options;
+
+fn list_zoo(format: ListFormat) -> String {
+    let (result, output) = common::run_zoo(
+        "name: zoo\n\
+                content:\n  \
+                  - animal: [mouse, cat]\n  \
+                  - flag: PENDING\n    \
+                    animal: parrot\n  \
+                  - flag: SOON\n    \
+                    animal: horse\n",
+        &Options {
+            list: Some(format),
+            ..Options::default()
+        },
+        &mut |_tile| panic!("No box must run"),
+    );
+    assert!(result.success);
+    output
+}
+
+#[test]
+fn test_list_text() {
+    let output = list_zoo(ListFormat::Text);
+    let line_vec: Vec<&str> = output.lines().collect();
+    assert_eq!(line_vec.len(), 5);
+    assert!(line_vec[0].starts_with("Warning(zoo.yaml:6:11): Unrecognized all uppercase flag"));
+    assert!(line_vec[1].starts_with("[zoo.yaml:3:10][0] #"));
+    assert!(
+        line_vec[1].ends_with(" zoo [animal=mouse] (animal=mouse, filepath=zoo.yaml, name=zoo)")
+    );
+    assert!(line_vec[2].ends_with(" zoo [animal=cat] (animal=cat, filepath=zoo.yaml, name=zoo)"));
+    assert!(line_vec[3].ends_with(" zoo (animal=horse, filepath=zoo.yaml, name=zoo)"));
+    assert_eq!(line_vec[4], "3 tile(s)");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_list_json() {
+    let output = list_zoo(ListFormat::Json);
+    assert!(output.starts_with("{\n  \"tiles\": [\n    {\n      \"id\": \""));
+    assert!(output.contains(
+        "\"location\": \"zoo.yaml:3:10\",\n      \
+        \"index\": 1,\n      \
+        \"name\": \"zoo [animal=cat]\",\n      \
+        \"data\": {\n        \
+        \"animal\": \"cat\",\n"
+    ));
+    assert!(output.contains(
+        "  \"diagnostics\": [\n    {\n      \
+        \"severity\": \"warning\",\n      \
+        \"location\": \"zoo.yaml:6:11\",\n"
+    ));
+    assert!(output.contains(
+        "  \"focused\": [],\n  \
+        \"pending\": [\n    {\n      \
+        \"location\": \"zoo.yaml:4:8\",\n      \
+        \"reason\": null\n    }\n  ],\n"
+    ));
+    assert!(!output.contains("Warning("));
+}
+
+#[cfg(not(feature = "json"))]
+#[test]
+fn test_list_json_without_the_json_feature() {
+    let options = Options {
+        list: Some(ListFormat::Json),
+        ..Options::default()
+    };
+    let (result, output) = common::run_zoo("animal: cat\n", &options, &mut |_tile| {
+        panic!("No box must run")
+    });
+    assert!(!result.success);
+    assert_eq!(
+        output,
+        "Error(SPECIMEN_LIST): The JSON list needs the json feature of specimen\n\
+        FAILURE -- No tile has been run because of the errors in the options\n"
+    );
+}
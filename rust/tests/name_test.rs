@@ -0,0 +1,32 @@
+mod common;
+
+use specimen::options::Options;
+
+#[test]
+fn test_tile_name() {
+    let mut name_vec = Vec::new();
+
+    let (result, _) = common::run_zoo(
+        "name: zoo\n\
+                content:\n  \
+                  - name: horse\n  \
+                  - name: animal matrix\n    \
+                    animal: [mouse, cat]\n    \
+                    leg: \"4\"\n",
+        &Options::default(),
+        &mut |_tile| {
+            name_vec.push(specimen::tile_name());
+            Ok(())
+        },
+    );
+
+    assert!(result.success);
+    assert_eq!(
+        name_vec,
+        vec![
+            Box::from("zoo > horse"),
+            Box::from("zoo > animal matrix [animal=mouse]"),
+            Box::from("zoo > animal matrix [animal=cat]"),
+        ]
+    );
+}
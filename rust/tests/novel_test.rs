@@ -37,14 +37,11 @@ fn turn_page_expect_page(input: &specimen::Dict) -> Result<(), Box<str>> {
     let mut book = deserialize_book(&input["book"]);
     let count = input["turn_page_count"].parse().unwrap();
     book.turn_page(count);
-    let expected_page = input["expected_left_page"].parse().unwrap();
-    match book.left_page == expected_page {
-        true => Ok(()),
-        false => Err(Box::from(format!(
-            "Expected page: {}, Actual page: {}",
-            expected_page, book.left_page
-        ))),
-    }
+    specimen::expect_equal(
+        &book.left_page.to_string(),
+        &input["expected_left_page"],
+        "left page",
+    )
 }
 
 fn get_page(input: &specimen::Dict) -> Result<(), Box<str>> {
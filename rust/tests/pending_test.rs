@@ -0,0 +1,80 @@
+mod common;
+
+use specimen::options::Options;
+
+#[test]
+fn test_pending_reason_and_expiry() {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+                - animal: horse\n  \
+                - flag: PENDING until=2999-01-01 reason=\"the unicorn has not arrived\"\n    \
+                  animal: unicorn\n  \
+                - flag: PENDING until=2000-01-01 reason=\"upstream bug\"\n    \
+                  animal: dodo\n",
+        &Options::default(),
+        &mut |_tile| Ok(()),
+    );
+
+    assert!(!result.success);
+    assert!(output.contains(
+        "EXPIRED[zoo.yaml:5:8]: The PENDING flag has expired: upstream bug (until 2000-01-01)\n\
+        PENDING[zoo.yaml:3:8]: the unicorn has not arrived (until 2999-01-01)\n\
+        Encountered 2 pending node(s)\n"
+    ));
+    assert!(output.contains("FAILURE -- 1 Passed"));
+}
+
+#[test]
+fn test_flag_locations() {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+                - flag: FOCUS\n    \
+                  content:\n      \
+                    - animal: horse\n      \
+                    - flag: PENDING reason=\"upstream bug\"\n        \
+                      animal: unicorn\n      \
+                    - flag: PENDING\n        \
+                      animal: dodo\n",
+        &Options {
+            verbose: true,
+            ..Options::default()
+        },
+        &mut |_tile| Ok(()),
+    );
+
+    assert!(result.success);
+    assert!(output.contains(
+        "focused: zoo.yaml:2:8\n\
+        pending: zoo.yaml:5:12 (upstream bug)\n\
+        pending: zoo.yaml:7:12\n\
+        Encountered 1 focused node(s) and 2 pending node(s)\n"
+    ));
+}
+
+#[test]
+fn test_pending_outside_of_the_focus() {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+            - flag: FOCUS\n    \
+              content:\n      \
+                - flag: FOCUS\n        \
+                  animal: horse\n  \
+            - flag: PENDING until=2000-01-01\n    \
+              animal: dodo\n",
+        &Options {
+            verbose: true,
+            ..Options::default()
+        },
+        &mut |_tile| Ok(()),
+    );
+
+    // The expired PENDING flag fails the run even though its node is not
+    // selected, and the ignored FOCUS flag is listed
+    assert!(!result.success);
+    assert!(output.contains("EXPIRED[zoo.yaml:6:8]: The PENDING flag has expired"));
+    assert!(output.contains(
+        "focused: zoo.yaml:2:8\n\
+        focused: zoo.yaml:4:12\n\
+        Encountered 1 focused node(s)\n"
+    ));
+}
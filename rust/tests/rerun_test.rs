@@ -0,0 +1,128 @@
+mod common;
+
+use specimen::options::Options;
+
+#[cfg(feature = "json")]
+fn run_animals(content: &str, options: &Options, failing: &str) -> Vec<String> {
+    let mut animal_vec = Vec::new();
+    common::run_zoo(content, options, &mut |tile| {
+        animal_vec.push(tile["animal"].to_string());
+        if &*tile["animal"] == failing {
+            return Err("the animal escaped".into());
+        }
+        Ok(())
+    });
+    animal_vec
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_rerun_last_failed() {
+    let state_path = std::env::temp_dir().join(format!(
+        "specimen-rerun-test-{}/last-failed.json",
+        std::process::id()
+    ));
+    let mut options = Options {
+        state_path: Some(state_path.clone()),
+        ..Options::default()
+    };
+
+    let content = "name: zoo\nanimal: [mouse, cat, dog]\n";
+    assert_eq!(
+        run_animals(content, &options, "cat"),
+        ["mouse", "cat", "dog"]
+    );
+
+    // The failed tile is found again after the YAML has moved
+    options.last_failed = true;
+    let moved_content = "\n\nname: zoo\nanimal: [dog, cat, mouse]\n";
+    assert_eq!(run_animals(moved_content, &options, "cat"), ["cat"]);
+
+    // Once the tile passes, all the tiles are run again
+    assert_eq!(run_animals(moved_content, &options, ""), ["cat"]);
+    assert_eq!(
+        run_animals(moved_content, &options, ""),
+        ["dog", "cat", "mouse"]
+    );
+
+    let _ = std::fs::remove_dir_all(state_path.parent().unwrap());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_rerun_unreadable_state() {
+    let state_path = std::env::temp_dir().join(format!(
+        "specimen-rerun-unreadable-test-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(&state_path, "{\"failed\": 3}").unwrap();
+    let mut options = Options {
+        state_path: Some(state_path.clone()),
+        last_failed: true,
+        ..Options::default()
+    };
+
+    let run =
+        |options: &Options| common::run_zoo("animal: [mouse, cat]\n", options, &mut |_| Ok(())).0;
+    let result = run(&options);
+    assert!(result.success);
+    assert_eq!(result.diagnostic_vec.len(), 1);
+    assert!(
+        result.diagnostic_vec[0].to_string().starts_with("Warning(")
+            && result.diagnostic_vec[0]
+                .message
+                .starts_with("The state file could not be read: ")
+    );
+
+    // The state file is rewritten, so break it again
+    std::fs::write(&state_path, "not json").unwrap();
+    options.strict = true;
+    assert!(!run(&options).success);
+
+    let _ = std::fs::remove_file(&state_path);
+}
+
+#[test]
+fn test_rerun_without_state() {
+    let options = Options {
+        last_failed: true,
+        ..Options::default()
+    };
+    let (result, animal_vec, output) = common::run_animals("animal: [mouse, cat]\n", &options);
+    assert!(result.success);
+    assert_eq!(animal_vec, ["mouse", "cat"]);
+    assert!(output.starts_with("No state file was found, all the tiles are run. "));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_rerun_unexpected_pass() {
+    let state_path = std::env::temp_dir().join(format!(
+        "specimen-rerun-xpass-test-{}.json",
+        std::process::id()
+    ));
+    let mut options = Options {
+        state_path: Some(state_path.clone()),
+        ..Options::default()
+    };
+    let content = "content:\n  \
+        - animal: mouse\n  \
+        - flag: XFAIL\n    \
+          animal: [cat, dog]\n";
+
+    // The unexpected pass of the dog is only a failure in strict mode
+    run_animals(content, &options, "cat");
+    options.last_failed = true;
+    assert_eq!(
+        run_animals(content, &options, "cat"),
+        ["mouse", "cat", "dog"]
+    );
+
+    options.strict = true;
+    options.last_failed = false;
+    run_animals(content, &options, "cat");
+    options.last_failed = true;
+    assert_eq!(run_animals(content, &options, "cat"), ["dog"]);
+
+    let _ = std::fs::remove_file(&state_path);
+}
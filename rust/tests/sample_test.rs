@@ -0,0 +1,80 @@
+mod common;
+
+use specimen::options::Options;
+use specimen::options::Shard;
+
+fn run_zoo(options: &Options) -> (Vec<String>, String) {
+    let mut tile_vec = Vec::new();
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+                - sample: 3\n    \
+                  size: [small, medium, big]\n    \
+                  animal: [mouse, cat, dog, horse]\n  \
+                - size: tall\n    \
+                  animal: [giraffe, ostrich]\n",
+        options,
+        &mut |tile| {
+            tile_vec.push(format!("{} {}", tile["size"], tile["animal"]));
+            Ok(())
+        },
+    );
+    assert!(result.success);
+    (tile_vec, output)
+}
+
+#[test]
+fn test_sample() {
+    let options = Options {
+        seed: Some(42),
+        ..Options::default()
+    };
+    let (tile_vec, output) = run_zoo(&options);
+    // The sampled tiles are run, along with the tiles of the small slabs
+    assert_eq!(tile_vec.len(), 5);
+    assert_eq!(&tile_vec[3..], ["tall giraffe", "tall ostrich"]);
+    assert!(output.contains("Sampled 3 of 12 tile(s) of zoo.yaml:2:10 with the seed 42: "));
+
+    // The same seed replays the same tiles
+    assert_eq!(run_zoo(&options).0, tile_vec);
+
+    // The option applies to the slabs without the sample key
+    let (tile_vec, output) = run_zoo(&Options {
+        sample_count: Some(1),
+        seed: Some(42),
+        ..Options::default()
+    });
+    assert_eq!(tile_vec.len(), 4);
+    assert!(output.contains("Sampled 1 of 2 tile(s) of zoo.yaml:5:8 with the seed 42: "));
+}
+
+#[test]
+fn test_sample_shards() {
+    // The shards draw the same samples, so they partition the sampled tiles
+    let mut tile_vec = Vec::new();
+    let mut sample_line_vec = Vec::new();
+    for index in 1..=3 {
+        let (shard_tile_vec, output) = run_zoo(&Options {
+            shard: Some(Shard { index, count: 3 }),
+            ..Options::default()
+        });
+        tile_vec.extend(shard_tile_vec);
+        sample_line_vec.push(
+            output
+                .lines()
+                .find(|line| line.starts_with("Sampled "))
+                .map(String::from),
+        );
+    }
+    assert!(sample_line_vec[0].is_some());
+    assert!(sample_line_vec
+        .iter()
+        .all(|line| *line == sample_line_vec[0]));
+
+    let (mut expected_vec, _) = run_zoo(&Options {
+        seed: Some(0),
+        ..Options::default()
+    });
+    tile_vec.sort();
+    expected_vec.sort();
+    assert_eq!(tile_vec, expected_vec);
+}
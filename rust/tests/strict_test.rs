@@ -0,0 +1,45 @@
+mod common;
+
+use specimen::diagnostic::Severity;
+use specimen::options::Options;
+
+fn run_zoo(strict: bool) -> (bool, Vec<String>, String) {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+                - flag: FOCUS\n    \
+                  animal: [horse, !FOCUS zebra]\n  \
+                - flag: SOON XFAIL\n    \
+                  animal: cat\n",
+        &Options {
+            strict,
+            ..Options::default()
+        },
+        &mut |_tile| Ok(()),
+    );
+    let error_vec = result
+        .diagnostic_vec
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    (result.success, error_vec, output)
+}
+
+#[test]
+fn test_strict() {
+    let (success, error_vec, _) = run_zoo(false);
+    assert!(success);
+    assert!(error_vec.is_empty());
+
+    let (success, error_vec, output) = run_zoo(true);
+    assert!(!success);
+    assert_eq!(
+        error_vec,
+        [
+            "Error(zoo.yaml:4:11): Unrecognized all uppercase flag \"SOON\". It has been ignored.",
+            "Error(zoo.yaml:2:11): The FOCUS flag is not allowed in strict mode",
+            "Error(zoo.yaml:3:28): The !FOCUS tag is not allowed in strict mode",
+        ]
+    );
+    assert!(output.contains("FAILURE -- 1 Passed"));
+}
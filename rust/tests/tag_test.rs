@@ -0,0 +1,25 @@
+mod common;
+
+use specimen::options::Options;
+use specimen::tag::TagExpression;
+
+#[test]
+fn test_tags() {
+    let (result, animal_vec, output) = common::run_animals(
+        "tags: smoke\n\
+                content:\n  \
+                  - animal: horse\n  \
+                  - tags: [slow, big]\n    \
+                    animal: [elephant, whale]\n  \
+                  - flag: PENDING\n    \
+                    animal: parrot\n",
+        &Options {
+            tag_expression: Some(TagExpression::parse("smoke & !slow").unwrap()),
+            ..Options::default()
+        },
+    );
+
+    assert!(result.success);
+    assert_eq!(animal_vec, ["horse"]);
+    assert!(output.contains("Encountered 1 pending node(s) and 2 tile(s) skipped by tag\n"));
+}
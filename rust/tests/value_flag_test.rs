@@ -0,0 +1,43 @@
+mod common;
+
+use specimen::options::Options;
+
+fn run_zoo(content: &str) -> (Vec<String>, String) {
+    let mut animal_vec = Vec::new();
+    let (result, output) = common::run_zoo(content, &Options::default(), &mut |tile| {
+        animal_vec.push(format!("{} {}", tile["size"], tile["animal"]));
+        Ok(())
+    });
+    assert!(result.success);
+    (animal_vec, output)
+}
+
+#[test]
+fn test_value_flags() {
+    // The pending values are not produced
+    let (animal_vec, output) = run_zoo(
+        "content:\n  \
+            - size: [small, !PENDING big]\n    \
+              animal: [mouse, !PENDING cat, dog]\n",
+    );
+    assert_eq!(animal_vec, ["small mouse", "small dog"]);
+    assert!(output.contains("Encountered 2 pending value(s)\n"));
+
+    // The focused values are the only values of their key, and the other
+    // slabs are not run
+    let (animal_vec, output) = run_zoo(
+        "content:\n  \
+            - size: [small, big]\n    \
+              animal: [mouse, !FOCUS cat, !PENDING dog]\n  \
+            - size: tall\n    \
+              animal: giraffe\n",
+    );
+    assert_eq!(animal_vec, ["small cat", "big cat"]);
+    assert!(output.contains("Encountered 1 focused value(s) and 1 pending value(s)\n"));
+
+    // The unknown tags are reported
+    let (animal_vec, output) = run_zoo("size: small\nanimal: !focus cat\n");
+    assert_eq!(animal_vec, ["small cat"]);
+    assert!(output
+        .contains("Warning(zoo.yaml:2:16): Unrecognized tag \"!focus\". It has been ignored."));
+}
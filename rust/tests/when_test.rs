@@ -0,0 +1,29 @@
+mod common;
+
+use specimen::options::Options;
+
+#[test]
+fn test_when() {
+    let mut options = Options::default();
+    options.predicate_map.insert("has_water".into(), || true);
+    options.predicate_map.insert("has_ice".into(), || false);
+    let (result, animal_vec, output) = common::run_animals(
+        "content:\n  \
+                - when: {env: PATH, predicate: has_water}\n    \
+                  content:\n      \
+                    - animal: fish\n      \
+                    - when: {predicate: has_ice}\n        \
+                      animal: [penguin, seal]\n  \
+                - when: {env: SPECIMEN_UNSET_VARIABLE}\n    \
+                  animal: camel\n",
+        &options,
+    );
+
+    assert!(result.success);
+    assert_eq!(animal_vec, ["fish"]);
+    assert!(output.contains(
+        "SKIPPED[zoo.yaml:5:12]: the predicate has_ice is false (2 tile(s))\n\
+        SKIPPED[zoo.yaml:7:8]: the environment variable SPECIMEN_UNSET_VARIABLE is not set (1 tile(s))\n\
+        Encountered 3 tile(s) skipped by condition\n"
+    ));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use specimen::options::Options;
+
+fn run_zoo(strict: bool) -> (bool, String) {
+    let (result, output) = common::run_zoo(
+        "content:\n  \
+            - animal: horse\n  \
+            - flag: XFAIL\n    \
+              content:\n      \
+                - animal: snail\n      \
+                - animal: horse\n",
+        &Options {
+            strict,
+            ..Options::default()
+        },
+        &mut |tile| match &*tile["animal"] {
+            "horse" => Ok(()),
+            animal => Err(format!("The {animal} cannot gallop").into()),
+        },
+    );
+    (result.success, output)
+}
+
+#[test]
+fn test_expected_failure() {
+    // Neither the expected failure nor the unexpected pass fail the run
+    let (success, output) = run_zoo(false);
+    assert!(success);
+    assert!(!output.contains("snail"));
+    assert!(output
+        .contains("XPASS[zoo.yaml:6:14][0]: The tile passed although it is expected to fail\n"));
+    assert!(output
+        .contains("SUCCESS -- 1 Passed | 0 Failed | 0 Aborted | 0 Panicked | 1 XFAIL | 1 XPASS\n"));
+    assert!(!output.contains("Failed tiles:"));
+
+    // In strict mode, the unexpected pass fails the run
+    let (success, output) = run_zoo(true);
+    assert!(!success);
+    assert!(output.contains("Failed tiles:\n  XPASS[zoo.yaml:6:14][0] #"));
+}
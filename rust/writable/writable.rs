@@ -1,4 +1,5 @@
 use std::io;
+use std::io::IsTerminal;
 
 #[derive(Debug)]
 pub enum Writable {
@@ -21,3 +22,14 @@ impl io::Write for Writable {
         }
     }
 }
+
+impl Writable {
+    /// returns true if the output is displayed in a terminal, as opposed to
+    /// being redirected to a file, a pipe or a buffer.
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Writable::Out(stdout) => stdout.is_terminal(),
+            Writable::Vec(_vec) => false,
+        }
+    }
+}
@@ -69,6 +69,9 @@ impl Display for Position {
 pub struct Yaml {
     pub data: YamlData,
     pub position: Position,
+    /// The local tag of a scalar, without its `!`, e.g. `FOCUS` for
+    /// `!FOCUS cat`
+    pub tag: Option<Box<str>>,
 }
 
 pub type List = Vec<Yaml>;
@@ -76,13 +79,18 @@ pub type Mapping = Vec<(Yaml, Yaml)>;
 
 impl Yaml {
     fn new(data: YamlData, position: Position) -> Yaml {
-        Yaml { data, position }
+        Yaml {
+            data,
+            position,
+            tag: None,
+        }
     }
 }
 
 pub static BAD_VALUE: Yaml = Yaml {
     data: YamlData::BadValue,
     position: Position { line: 0, column: 0 },
+    tag: None,
 };
 
 // parse f64 as Core schema
@@ -179,7 +187,13 @@ impl MarkedEventReceiver for YamlLoader {
                     YamlData::from_str(&v)
                 };
 
-                self.insert_new_node((Yaml::new(yaml_data, position), aid));
+                let mut node = Yaml::new(yaml_data, position);
+                if let Some(TokenType::Tag(ref handle, ref suffix)) = tag {
+                    if handle == "!" {
+                        node.tag = Some(suffix.as_str().into());
+                    }
+                }
+                self.insert_new_node((node, aid));
             }
             Event::Alias(id) => {
                 let n = match self.anchor_map.get(&id) {